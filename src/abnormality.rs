@@ -0,0 +1,265 @@
+// abnormality pages of LOR combat
+
+use crate::damage::DamageModifiers;
+use crate::object::{AttackDice, KeyPage, Resistance};
+use crate::power::PowerModifier;
+use crate::status::StatusRef;
+
+//pages offered on each emotion level up
+pub const OFFERED: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Scope {
+    Owner,
+    //every unit on the owner's side
+    Team,
+}
+
+//passive picked on an emotion level up, kept for the rest of the
+//reception; everything but the statuses is applied once when picked,
+//statuses are granted again at every scene start
+#[derive(Clone)]
+pub struct AbnormalityPage {
+    pub name: String,
+    //emotion level the page is offered at
+    pub level: u32,
+    pub scope: Scope,
+    pub power: Vec<PowerModifier>,
+    //(attack type, health, stagger), None keeps what the unit has
+    pub resistances: Vec<(AttackDice, Option<Resistance>, Option<Resistance>)>,
+    pub outgoing: DamageModifiers,
+    pub incoming: DamageModifiers,
+    pub statuses: Vec<(StatusRef, i32)>,
+}
+
+impl AbnormalityPage {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    //settle the page on one unit
+    pub fn apply(&self, key: &mut KeyPage) {
+        key.power.extend(self.power.iter().cloned());
+        //pages stack, each only replaces the resistances it names
+        for (atype, health, stagger) in &self.resistances {
+            let (old_health, old_stagger) = key.resistances.get(atype);
+            let health = health.clone().unwrap_or_else(|| old_health.clone());
+            let stagger = stagger.clone().unwrap_or_else(|| old_stagger.clone());
+            key.resistances.set(atype, health, stagger);
+        }
+        key.outgoing = key.outgoing + self.outgoing;
+        key.incoming = key.incoming + self.incoming;
+        key.abnormalities.push(self.clone());
+    }
+}
+
+pub struct AbnormalityPageBuilder {
+    name: String,
+    level: u32,
+    scope: Scope,
+    power: Vec<PowerModifier>,
+    resistances: Vec<(AttackDice, Option<Resistance>, Option<Resistance>)>,
+    outgoing: DamageModifiers,
+    incoming: DamageModifiers,
+    statuses: Vec<(StatusRef, i32)>,
+}
+
+impl AbnormalityPageBuilder {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            level: 1,
+            scope: Scope::Owner,
+            power: Vec::new(),
+            resistances: Vec::new(),
+            outgoing: DamageModifiers::default(),
+            incoming: DamageModifiers::default(),
+            statuses: Vec::new(),
+        }
+    }
+
+    pub fn level(mut self, level: u32) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn scope(mut self, scope: Scope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    pub fn power(mut self, modifier: PowerModifier) -> Self {
+        self.power.push(modifier);
+        self
+    }
+
+    pub fn resistance(
+        mut self,
+        atype: AttackDice,
+        health: Resistance,
+        stagger: Resistance,
+    ) -> Self {
+        self.resistances.push((atype, Some(health), Some(stagger)));
+        self
+    }
+
+    pub fn health_resistance(mut self, atype: AttackDice, health: Resistance) -> Self {
+        self.resistances.push((atype, Some(health), None));
+        self
+    }
+
+    pub fn stagger_resistance(mut self, atype: AttackDice, stagger: Resistance) -> Self {
+        self.resistances.push((atype, None, Some(stagger)));
+        self
+    }
+
+    pub fn outgoing(mut self, outgoing: DamageModifiers) -> Self {
+        self.outgoing = outgoing;
+        self
+    }
+
+    pub fn incoming(mut self, incoming: DamageModifiers) -> Self {
+        self.incoming = incoming;
+        self
+    }
+
+    pub fn status(mut self, status: StatusRef, stacks: i32) -> Self {
+        self.statuses.push((status, stacks));
+        self
+    }
+
+    pub fn build(self) -> AbnormalityPage {
+        AbnormalityPage {
+            name: self.name,
+            level: self.level,
+            scope: self.scope,
+            power: self.power,
+            resistances: self.resistances,
+            outgoing: self.outgoing,
+            incoming: self.incoming,
+            statuses: self.statuses,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle::{BattleBuilder, Side};
+    use crate::damage::DamageModifier;
+    use crate::emotion::CoinSource;
+    use crate::fixtures::key;
+    use crate::object::{CombatDice, DiceType, NormalDice};
+    use crate::power::{DiceFilter, PowerSource};
+    use crate::status::Strength;
+    use std::sync::Arc;
+
+    #[test]
+    fn abnormality_apply() {
+        let page = AbnormalityPageBuilder::new("Pleasure")
+            .power(PowerModifier::new(
+                PowerSource::Passive,
+                1,
+                DiceFilter::Attack(AttackDice::Pierce),
+            ))
+            .resistance(AttackDice::Blunt, Resistance::Endured, Resistance::Weak)
+            .outgoing(DamageModifiers {
+                health: DamageModifier {
+                    percent: 25,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .status(Arc::new(Strength), 2)
+            .build();
+        let mut key = key();
+        page.apply(&mut key);
+
+        assert_eq!(key.power.len(), 1);
+        let (health, stagger) = key.resistances.get(&AttackDice::Blunt);
+        assert_eq!((health.number(), stagger.number()), (0.5, 1.5));
+        assert_eq!(key.outgoing.health.percent, 25);
+
+        //statuses come back every scene
+        let attack = DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Slash)));
+        for _ in 0..2 {
+            key.start_scene();
+            assert_eq!(key.statuses.power(&attack), 2);
+            key.end_scene();
+            assert_eq!(key.statuses.power(&attack), 0);
+        }
+    }
+
+    #[test]
+    fn abnormality_layering() {
+        let mut key = key();
+        AbnormalityPageBuilder::new("Bloodbath")
+            .resistance(
+                AttackDice::Slash,
+                Resistance::Ineffective,
+                Resistance::Fatal,
+            )
+            .health_resistance(AttackDice::Blunt, Resistance::Endured)
+            .build()
+            .apply(&mut key);
+        AbnormalityPageBuilder::new("Heartbeat")
+            .stagger_resistance(AttackDice::Blunt, Resistance::Weak)
+            .health_resistance(AttackDice::Slash, Resistance::Normal)
+            .build()
+            .apply(&mut key);
+
+        let number =
+            |(health, stagger): (&Resistance, &Resistance)| (health.number(), stagger.number());
+        assert_eq!(number(key.resistances.get(&AttackDice::Blunt)), (0.5, 1.5));
+        assert_eq!(number(key.resistances.get(&AttackDice::Slash)), (1.0, 2.0));
+        assert_eq!(number(key.resistances.get(&AttackDice::Pierce)), (1.0, 1.0));
+    }
+
+    #[test]
+    fn abnormality_selection() {
+        let page = |name: &str, level, scope| {
+            AbnormalityPageBuilder::new(name)
+                .level(level)
+                .scope(scope)
+                .incoming(DamageModifiers {
+                    stagger: DamageModifier {
+                        reduction: 1,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .build()
+        };
+        let mut battle = BattleBuilder::new()
+            .ally(key())
+            .ally(key())
+            .ally(key())
+            .enemy(key())
+            .abnormality(page("Alone", 1, Scope::Owner))
+            .abnormality(page("Together", 1, Scope::Team))
+            .abnormality(page("Later", 2, Scope::Team))
+            //always the last page offered, enemies pass
+            .select(|_, side, _, offered| match side {
+                Side::Ally => Some(offered.len() - 1),
+                Side::Enemy => None,
+            })
+            .build();
+        battle.allies[2].curhealth = 0;
+        for _ in 0..3 {
+            battle.allies[0].emotion.gain(CoinSource::ClashWin);
+            battle.enemies[0].emotion.gain(CoinSource::ClashLose);
+        }
+
+        let logs = battle.end_scene();
+        assert_eq!(logs[0].picked, vec!["Together".to_string()]);
+        assert!(logs[2].picked.is_empty());
+        assert_eq!(logs[2].levels, 1);
+        //team pages reach every ally, nothing left the pool for the enemy
+        assert_eq!(battle.allies[1].incoming.stagger.reduction, 1);
+        assert_eq!(battle.allies[1].abnormalities.len(), 1);
+        //but not the dead
+        assert!(battle.allies[2].abnormalities.is_empty());
+        assert_eq!(battle.enemies[0].incoming.stagger.reduction, 0);
+        assert_eq!(battle.pool.len(), 2);
+    }
+}
@@ -0,0 +1,694 @@
+// scenes and acts of LOR combat
+
+use crate::abnormality::{AbnormalityPage, Scope, OFFERED};
+use crate::clash::ClashReport;
+use crate::deck::SCENE_DRAW;
+use crate::emotion::CoinSource;
+use crate::object::{CombatPage, KeyPage};
+use rand::Rng;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    Ally,
+    Enemy,
+}
+
+impl Side {
+    pub fn opponent(&self) -> Self {
+        match self {
+            Self::Ally => Self::Enemy,
+            Self::Enemy => Self::Ally,
+        }
+    }
+}
+
+//die `dice` of unit `unit` on `side`, what actions are put on and aimed at
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Slot {
+    pub side: Side,
+    pub unit: usize,
+    pub dice: usize,
+}
+
+impl Slot {
+    pub fn new(side: Side, unit: usize, dice: usize) -> Self {
+        Self { side, unit, dice }
+    }
+}
+
+//one combat page put on a speed die, aimed at a speed die of the other side
+#[derive(Clone)]
+pub struct Action {
+    pub slot: Slot,
+    pub page: CombatPage,
+    pub target: Slot,
+}
+
+impl Action {
+    pub fn new(slot: Slot, page: CombatPage, target: Slot) -> Self {
+        Self { slot, page, target }
+    }
+}
+
+//one resolved action, the report is seen from the acting unit; `clashed`
+//is false for one-sided attacks, `redirected` is set when the target's
+//page was pulled off its own target to clash
+#[derive(Clone)]
+pub struct Exchange {
+    pub slot: Slot,
+    pub target: Slot,
+    pub clashed: bool,
+    pub redirected: bool,
+    pub report: ClashReport,
+}
+
+//emotion coins one unit gained over a scene and the level they left it at
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmotionLog {
+    pub side: Side,
+    pub unit: usize,
+    pub coins: Vec<CoinSource>,
+    pub level: u32,
+    pub levels: u32,
+    //abnormality pages picked for those levels
+    pub picked: Vec<String>,
+}
+
+//picks one of the offered abnormality pages for unit `usize` of `Side`,
+//None to pass; a UI or an AI plugs in here
+pub type Selector =
+    Arc<dyn Fn(&Battle, Side, usize, &[AbnormalityPage]) -> Option<usize> + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub struct SceneReport {
+    pub scene: u32,
+    pub exchanges: Vec<Exchange>,
+    pub emotion: Vec<EmotionLog>,
+}
+
+#[derive(Clone)]
+pub struct Battle {
+    pub allies: Vec<KeyPage>,
+    pub enemies: Vec<KeyPage>,
+    //scenes started so far
+    pub scene: u32,
+    //abnormality pages not picked yet, offered by emotion level
+    pub pool: Vec<AbnormalityPage>,
+    pub select: Option<Selector>,
+}
+
+impl Battle {
+    pub fn units(&self, side: Side) -> &Vec<KeyPage> {
+        match side {
+            Side::Ally => &self.allies,
+            Side::Enemy => &self.enemies,
+        }
+    }
+
+    pub fn units_mut(&mut self, side: Side) -> &mut Vec<KeyPage> {
+        match side {
+            Side::Ally => &mut self.allies,
+            Side::Enemy => &mut self.enemies,
+        }
+    }
+
+    pub fn unit(&self, side: Side, index: usize) -> Option<&KeyPage> {
+        self.units(side).get(index)
+    }
+
+    pub fn is_defeated(&self, side: Side) -> bool {
+        self.units(side).iter().all(|x| x.health() <= 0)
+    }
+
+    //the side still standing once the other is fully defeated
+    pub fn winner(&self) -> Option<Side> {
+        if self.is_defeated(Side::Enemy) {
+            return Some(Side::Ally);
+        }
+        if self.is_defeated(Side::Ally) {
+            return Some(Side::Enemy);
+        }
+        None
+    }
+
+    fn pair(&mut self, slot: Slot, target: Slot) -> (&mut KeyPage, &mut KeyPage) {
+        match slot.side {
+            Side::Ally => (&mut self.allies[slot.unit], &mut self.enemies[target.unit]),
+            Side::Enemy => (&mut self.enemies[slot.unit], &mut self.allies[target.unit]),
+        }
+    }
+
+    fn living_mut(&mut self) -> impl Iterator<Item = &mut KeyPage> {
+        self.allies
+            .iter_mut()
+            .chain(self.enemies.iter_mut())
+            .filter(|x| x.health() > 0)
+    }
+
+    //rolled value of a speed die, None once its unit cannot act
+    pub fn speed(&self, slot: Slot) -> Option<i32> {
+        self.unit(slot.side, slot.unit)?
+            .get(slot.dice)
+            .map(|x| x.cur())
+    }
+
+    //an attacker strictly faster than the die it targets pulls that die's
+    //page into a clash; ties do not, and a die whose unit cannot act
+    //(staggered, get() returns None) is always outsped
+    pub fn can_redirect(&self, attacker: Slot, target: Slot) -> bool {
+        match (
+            self.unit(attacker.side, attacker.unit),
+            self.unit(target.side, target.unit),
+        ) {
+            (Some(own), Some(other)) => own.outspeeds(attacker.dice, other, target.dice),
+            _ => false,
+        }
+    }
+
+    //an action is taken from a usable die and aims at a die the other side
+    //has, whether or not its unit can act
+    fn is_valid(&self, action: &Action) -> bool {
+        let (slot, target) = (action.slot, action.target);
+        if slot.side == target.side || self.speed(slot).is_none() {
+            return false;
+        }
+        if slot.dice >= self.units(slot.side)[slot.unit].reserved_slot() {
+            return false;
+        }
+        match self.unit(target.side, target.unit) {
+            None => false,
+            Some(key) => target.dice < key.reserved_slot(),
+        }
+    }
+
+    //statuses tick, every living unit rolls its speed dice and draws: the
+    //opening hand on the first scene, SCENE_DRAW pages after that
+    pub fn start_scene_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.scene += 1;
+        let first = self.scene == 1;
+        for unit in self.living_mut() {
+            unit.start_scene_with(rng);
+            if first {
+                unit.hand.open_with(rng);
+            } else {
+                unit.hand.draw_with(SCENE_DRAW, rng);
+            }
+            unit.settle_draws_with(rng);
+        }
+    }
+
+    //actions resolve fastest first. an action clashes the page on the die
+    //it targets when that page aims back at it, or when it outspeeds that
+    //die and redirects the page; otherwise it is one-sided. actions a unit
+    //cannot take, on a die it does not have or used twice, or with a page
+    //it cannot afford or that is not in its hand, are dropped; pages are
+    //paid for in the order given and leave the hand.
+    //a unit staggered or killed mid scene loses the rest, and an action
+    //that never resolves gives its light back
+    pub fn resolve_with<R: Rng + ?Sized>(
+        &mut self,
+        actions: Vec<Action>,
+        rng: &mut R,
+    ) -> SceneReport {
+        let mut report = SceneReport {
+            scene: self.scene,
+            ..Default::default()
+        };
+        let mut used = Vec::new();
+        let mut valid = Vec::new();
+        for action in actions {
+            if used.contains(&action.slot) || !self.is_valid(&action) {
+                continue;
+            }
+            let slot = action.slot;
+            if self.units_mut(slot.side)[slot.unit]
+                .play(&action.page)
+                .is_ok()
+            {
+                used.push(slot);
+                valid.push(Some(action));
+            }
+        }
+        let mut actions = valid;
+        let mut order: Vec<(usize, i32)> = actions
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (i, self.speed(x.as_ref().unwrap().slot).unwrap()))
+            .collect();
+        order.sort_by_key(|&(_, speed)| -speed);
+
+        for (index, _) in order {
+            let Some(action) = actions[index].take() else {
+                continue;
+            };
+            let (slot, target) = (action.slot, action.target);
+            if self.speed(slot).is_none() || self.units(target.side)[target.unit].health() <= 0 {
+                self.units_mut(slot.side)[slot.unit].restore_light(action.page.cost());
+                continue;
+            }
+            //the page still waiting on the targeted die, if it can act
+            let waiting = actions.iter().position(|x| {
+                x.as_ref()
+                    .is_some_and(|x| x.slot == target && self.speed(x.slot).is_some())
+            });
+            let (answer, redirected) = match waiting {
+                Some(index) if actions[index].as_ref().unwrap().target == slot => {
+                    (Some(index), false)
+                }
+                Some(index) if self.can_redirect(slot, target) => (Some(index), true),
+                _ => (None, false),
+            };
+
+            let (own, other_key) = self.pair(slot, target);
+            let mut page = action.page;
+            own.empower(&mut page);
+            //pages that cannot clash, like two counter dice meeting, leave
+            //the answer waiting for its own turn and hit one-sided
+            let clash = answer.and_then(|index| {
+                let mut other = actions[index].as_ref().unwrap().page.clone();
+                other_key.empower(&mut other);
+                let clash = page.clone().try_clash(other, own, other_key, rng).ok()?;
+                actions[index] = None;
+                Some(clash)
+            });
+            let clashed = clash.is_some();
+            let clash = match clash {
+                Some(clash) => clash,
+                None => page.one_sided(own, other_key, rng),
+            };
+            own.settle_draws_with(rng);
+            other_key.settle_draws_with(rng);
+            report.exchanges.push(Exchange {
+                slot,
+                target,
+                clashed,
+                redirected: redirected && clashed,
+                report: clash,
+            });
+        }
+
+        report
+    }
+
+    //upkeep: statuses decay, staggers count down, every living unit
+    //recovers 1 light and turns its emotion coins into levels, choosing an
+    //abnormality page for each level gained
+    pub fn end_scene(&mut self) -> Vec<EmotionLog> {
+        let mut logs = Vec::new();
+        for side in [Side::Ally, Side::Enemy] {
+            for (index, unit) in self.units_mut(side).iter_mut().enumerate() {
+                if unit.health() <= 0 {
+                    continue;
+                }
+                unit.end_scene();
+                unit.restore_light(1);
+                let coins = unit.emotion.gained.clone();
+                let levels = unit.level_up();
+                logs.push(EmotionLog {
+                    side,
+                    unit: index,
+                    coins,
+                    level: unit.emotion.level,
+                    levels,
+                    picked: Vec::new(),
+                });
+            }
+        }
+        for log in logs.iter_mut() {
+            for level in (log.level - log.levels + 1)..=log.level {
+                if let Some(page) = self.choose(log.side, log.unit, level) {
+                    log.picked.push(page);
+                }
+            }
+        }
+        logs
+    }
+
+    //offer up to OFFERED pool pages of `level` to the selector and apply
+    //the one it picks, to the unit or its whole side
+    fn choose(&mut self, side: Side, unit: usize, level: u32) -> Option<String> {
+        let select = self.select.clone()?;
+        let offered: Vec<AbnormalityPage> = self
+            .pool
+            .iter()
+            .filter(|x| x.level == level)
+            .take(OFFERED)
+            .cloned()
+            .collect();
+        if offered.is_empty() {
+            return None;
+        }
+        let page = offered.get(select(self, side, unit, &offered)?)?;
+        self.pool.retain(|x| x.name() != page.name());
+        match page.scope {
+            Scope::Owner => page.apply(&mut self.units_mut(side)[unit]),
+            Scope::Team => self
+                .units_mut(side)
+                .iter_mut()
+                .filter(|x| x.health() > 0)
+                .for_each(|x| page.apply(x)),
+        }
+        Some(page.name().to_string())
+    }
+
+    //one full scene, `plan` sees the rolled speed dice and hands back the
+    //actions of both sides
+    pub fn play_scene_with<R, P>(&mut self, plan: &mut P, rng: &mut R) -> SceneReport
+    where
+        R: Rng + ?Sized,
+        P: FnMut(&Battle) -> Vec<Action>,
+    {
+        self.start_scene_with(rng);
+        let actions = plan(self);
+        let mut report = self.resolve_with(actions, rng);
+        report.emotion = self.end_scene();
+        report
+    }
+
+    //play scenes until one side is defeated or `limit` scenes have passed
+    pub fn run_with<R, P>(&mut self, limit: u32, mut plan: P, rng: &mut R) -> Option<Side>
+    where
+        R: Rng + ?Sized,
+        P: FnMut(&Battle) -> Vec<Action>,
+    {
+        while self.winner().is_none() && self.scene < limit {
+            self.play_scene_with(&mut plan, rng);
+        }
+        self.winner()
+    }
+}
+
+#[derive(Default)]
+pub struct BattleBuilder {
+    allies: Vec<KeyPage>,
+    enemies: Vec<KeyPage>,
+    pool: Vec<AbnormalityPage>,
+    select: Option<Selector>,
+}
+
+impl BattleBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ally(mut self, key: KeyPage) -> Self {
+        self.allies.push(key);
+        self
+    }
+
+    pub fn enemy(mut self, key: KeyPage) -> Self {
+        self.enemies.push(key);
+        self
+    }
+
+    pub fn abnormality(mut self, page: AbnormalityPage) -> Self {
+        self.pool.push(page);
+        self
+    }
+
+    pub fn select<F>(mut self, select: F) -> Self
+    where
+        F: Fn(&Battle, Side, usize, &[AbnormalityPage]) -> Option<usize> + Send + Sync + 'static,
+    {
+        self.select = Some(Arc::new(select));
+        self
+    }
+
+    pub fn build(self) -> Battle {
+        Battle {
+            allies: self.allies,
+            enemies: self.enemies,
+            scene: 0,
+            pool: self.pool,
+            select: self.select,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::{Deck, DECK_SIZE, OPENING_HAND};
+    use crate::effect::{DrawPages, Trigger};
+    use crate::error::CombatError;
+    use crate::fixtures::slash_page;
+    use crate::object::{
+        AttackDice, CombatDice, CombatPageBuilder, DefenseDice, Dice, DiceType, KeyPageBuilder,
+        NormalDice,
+    };
+    use std::sync::Arc;
+
+    fn speed(value: i32) -> Dice {
+        Dice::new(DiceType::Speed, value, value)
+    }
+
+    fn unit(value: i32) -> KeyPage {
+        KeyPageBuilder::new()
+            .health(20)
+            .stagger(40)
+            .lights(3)
+            .speed(speed(value))
+            .build()
+    }
+
+    fn ally(unit: usize, dice: usize) -> Slot {
+        Slot::new(Side::Ally, unit, dice)
+    }
+
+    fn enemy(unit: usize, dice: usize) -> Slot {
+        Slot::new(Side::Enemy, unit, dice)
+    }
+
+    #[test]
+    fn scene_order_and_clash() {
+        let mut battle = BattleBuilder::new()
+            .ally(unit(5))
+            .ally(unit(2))
+            .enemy(unit(3))
+            .build();
+        battle.allies[0].curlights = 1;
+        battle.start_scene_with(&mut crate::rng::seeded(0));
+        let actions = vec![
+            Action::new(ally(1, 0), slash_page(4), enemy(0, 0)),
+            Action::new(enemy(0, 0), slash_page(6), ally(1, 0)),
+            Action::new(ally(0, 0), slash_page(3), enemy(0, 0)),
+            //one die, one page
+            Action::new(ally(0, 0), slash_page(9), enemy(0, 0)),
+            //a die the unit does not have
+            Action::new(ally(1, 1), slash_page(9), enemy(0, 0)),
+        ];
+        let report = battle.resolve_with(actions, &mut crate::rng::seeded(0));
+        battle.end_scene();
+
+        //speed 5 outspeeds the speed 3 enemy die and pulls its page in, 6 beats 3
+        assert_eq!(report.exchanges.len(), 2);
+        assert_eq!(report.exchanges[0].slot, ally(0, 0));
+        assert!(report.exchanges[0].clashed && report.exchanges[0].redirected);
+        assert_eq!(battle.allies[0].health(), 14);
+        //speed 2 arrives after the enemy page is gone and hits one-sided
+        assert!(!report.exchanges[1].clashed);
+        assert_eq!(battle.enemies[0].health(), 16);
+        assert_eq!(battle.allies[1].health(), 20);
+        assert_eq!(battle.allies[0].curlights, 2);
+        assert_eq!(battle.scene, 1);
+    }
+
+    #[test]
+    fn targeting_ties_and_stagger() {
+        let mut battle = BattleBuilder::new()
+            .ally(unit(4))
+            .ally(unit(4))
+            .enemy(unit(4))
+            .enemy(unit(1))
+            .build();
+        battle.start_scene_with(&mut crate::rng::seeded(0));
+        assert!(!battle.can_redirect(ally(0, 0), enemy(0, 0)));
+        assert!(battle.can_redirect(ally(0, 0), enemy(1, 0)));
+
+        battle.enemies[1].curstagger = 0;
+        battle.enemies[1].update_stagger();
+        assert_eq!(battle.speed(enemy(1, 0)), None);
+        assert!(battle.can_redirect(ally(1, 0), enemy(1, 0)));
+        assert!(!battle.can_redirect(enemy(1, 0), ally(1, 0)));
+        //staggered or not, only the dice a unit has can be targeted
+        assert!(!battle.is_valid(&Action::new(ally(1, 0), slash_page(3), enemy(1, 3))));
+        assert!(!battle.is_valid(&Action::new(ally(1, 0), slash_page(3), enemy(1, 1))));
+
+        let actions = vec![
+            //a tie redirects nothing, the enemy page stays on ally 1
+            Action::new(ally(0, 0), slash_page(5), enemy(0, 0)),
+            Action::new(enemy(0, 0), slash_page(2), ally(1, 0)),
+            //staggered units never answer
+            Action::new(ally(1, 0), slash_page(3), enemy(1, 0)),
+            Action::new(enemy(1, 0), slash_page(9), ally(1, 0)),
+        ];
+        let report = battle.resolve_with(actions, &mut crate::rng::seeded(0));
+        assert_eq!(report.exchanges.len(), 3);
+        assert!(report.exchanges.iter().all(|x| !x.clashed));
+        assert_eq!(battle.enemies[0].health(), 15);
+        assert_eq!(battle.allies[1].health(), 18);
+        //fatal while staggered
+        assert_eq!(battle.enemies[1].health(), 14);
+    }
+
+    #[test]
+    fn battle_until_defeat() {
+        let mut battle = BattleBuilder::new().ally(unit(4)).enemy(unit(1)).build();
+        let winner = battle.run_with(
+            10,
+            |battle| {
+                let mut actions = vec![Action::new(ally(0, 0), slash_page(6), enemy(0, 0))];
+                if battle.enemies[0].gets().is_some() {
+                    actions.push(Action::new(enemy(0, 0), slash_page(2), ally(0, 0)));
+                }
+                actions
+            },
+            &mut crate::rng::seeded(0),
+        );
+        assert_eq!(winner, Some(Side::Ally));
+        assert!(battle.is_defeated(Side::Enemy));
+        //6 a scene through 20 health
+        assert_eq!(battle.scene, 4);
+        assert_eq!(battle.allies[0].health(), 20);
+    }
+
+    #[test]
+    fn light_costs() {
+        let mut key = unit(3);
+        key.speed.insert(0, speed(2));
+        let mut battle = BattleBuilder::new().ally(key).enemy(unit(1)).build();
+        battle.allies[0].curlights = 2;
+        battle.start_scene_with(&mut crate::rng::seeded(0));
+        let costly = |value, cost| {
+            let mut page = slash_page(value);
+            page.cost = cost;
+            page
+        };
+        let actions = vec![
+            Action::new(ally(0, 0), costly(3, 2), enemy(0, 0)),
+            //1 more than what is left
+            Action::new(ally(0, 1), costly(4, 1), enemy(0, 0)),
+        ];
+        let report = battle.resolve_with(actions, &mut crate::rng::seeded(0));
+        assert_eq!(report.exchanges.len(), 1);
+        assert_eq!(battle.allies[0].curlights, 0);
+        assert_eq!(battle.enemies[0].health(), 17);
+
+        let logs = battle.end_scene();
+        assert_eq!(battle.allies[0].curlights, 1);
+        //the one-sided hit is a single coin each way
+        assert_eq!(logs[0].coins, vec![CoinSource::DealDamage]);
+        assert_eq!(logs[1].side, Side::Enemy);
+        assert_eq!(logs[1].coins, vec![CoinSource::TakeDamage]);
+        assert_eq!(logs[1].levels, 0);
+        battle.allies[0].restore_light(5);
+        assert_eq!(battle.allies[0].curlights, 3);
+    }
+
+    #[test]
+    fn counter_pages_never_clash() {
+        let counter = |dice| {
+            CombatPageBuilder::new()
+                .dice(Dice::new(DiceType::Combat(CombatDice::Counter(dice)), 3, 3))
+                .build()
+        };
+        let mut battle = BattleBuilder::new().ally(unit(4)).enemy(unit(4)).build();
+        battle.start_scene_with(&mut crate::rng::seeded(0));
+        let actions = vec![
+            Action::new(
+                ally(0, 0),
+                counter(NormalDice::Defense(DefenseDice::Evade)),
+                enemy(0, 0),
+            ),
+            Action::new(
+                enemy(0, 0),
+                counter(NormalDice::Attack(AttackDice::Slash)),
+                ally(0, 0),
+            ),
+        ];
+        let report = battle.resolve_with(actions, &mut crate::rng::seeded(0));
+        //both go one-sided, where counter dice only wait on the reserved slot
+        assert_eq!(report.exchanges.len(), 2);
+        assert!(report.exchanges.iter().all(|x| !x.clashed));
+        assert_eq!(battle.allies[0].reserved.len(), 1);
+        assert_eq!(battle.enemies[0].reserved.len(), 1);
+        assert_eq!(battle.allies[0].health(), 20);
+    }
+
+    #[test]
+    fn dropped_actions_refund_light() {
+        let mut battle = BattleBuilder::new()
+            .ally(unit(4))
+            .enemy(unit(4))
+            .enemy(unit(1))
+            .build();
+        battle.start_scene_with(&mut crate::rng::seeded(0));
+        battle.allies[0].curstagger = 3;
+        let mut page = slash_page(3);
+        page.cost = 2;
+        let actions = vec![
+            //ties never redirect, the enemy hits first and staggers ally 0
+            Action::new(enemy(0, 0), slash_page(5), ally(0, 0)),
+            Action::new(ally(0, 0), page, enemy(1, 0)),
+        ];
+        let report = battle.resolve_with(actions, &mut crate::rng::seeded(0));
+        assert_eq!(report.exchanges.len(), 1);
+        assert!(battle.allies[0].is_staggered());
+        assert_eq!(battle.allies[0].curlights, 3);
+        assert_eq!(battle.enemies[1].health(), 20);
+    }
+
+    #[test]
+    fn hands_through_scenes() {
+        let page = |x: usize| {
+            CombatPageBuilder::new()
+                .name(&format!("Page {}", x))
+                .dice(Dice::new(
+                    DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Slash))),
+                    2,
+                    2,
+                ))
+                .effect(Arc::new(DrawPages {
+                    trigger: Trigger::OnUse,
+                    amount: 2,
+                }))
+                .build()
+        };
+        let deck = Deck::new((0..DECK_SIZE).map(page).collect());
+        let key = KeyPageBuilder::new()
+            .health(20)
+            .stagger(20)
+            .speed(speed(3))
+            .deck(deck)
+            .build();
+        let mut battle = BattleBuilder::new().ally(key).enemy(unit(1)).build();
+        let mut rng = crate::rng::seeded(0);
+
+        battle.start_scene_with(&mut rng);
+        let hand = &battle.allies[0].hand;
+        assert_eq!(hand.len(), OPENING_HAND);
+        let played = hand.get(0).unwrap().clone();
+        let missing = page(DECK_SIZE);
+        assert_eq!(
+            battle.allies[0].clone().play(&missing),
+            Err(CombatError::PageNotInHand(missing.name().to_string()))
+        );
+        let actions = vec![
+            //rejected without taking the die
+            Action::new(ally(0, 0), missing, enemy(0, 0)),
+            Action::new(ally(0, 0), played, enemy(0, 0)),
+        ];
+        let report = battle.resolve_with(actions, &mut rng);
+        assert_eq!(report.exchanges.len(), 1);
+        battle.end_scene();
+        //one played, two drawn by its effect
+        let hand = &battle.allies[0].hand;
+        assert_eq!(hand.len(), OPENING_HAND + 1);
+        assert_eq!(hand.discard.len(), 1);
+        assert_eq!(battle.allies[0].draws, 0);
+
+        battle.start_scene_with(&mut rng);
+        assert_eq!(battle.allies[0].hand.len(), OPENING_HAND + 2);
+    }
+}
@@ -0,0 +1,319 @@
+// clash records of LOR combat
+
+use crate::damage::HitBreakdown;
+use crate::error::CombatError;
+use crate::object::{
+    AttackDice, CombatDice, CombatPage, DefenseDice, Dice, DiceType, Evaluation, KeyPage,
+    NormalDice, Winner,
+};
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ClashKind {
+    //an attack die won, or hit one-sided
+    Hit,
+    //a block die won, the loser takes the difference as stagger damage
+    Block,
+    //an evade die won, its owner recovers stagger
+    Evade,
+    Draw,
+}
+
+//what one side should take from a record, before resistances
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Damage {
+    pub attack: Option<AttackDice>,
+    pub health: i32,
+    pub stagger: i32,
+    pub recovery: i32,
+}
+
+impl Damage {
+    pub fn is_empty(&self) -> bool {
+        self.health == 0 && self.stagger == 0 && self.recovery == 0
+    }
+}
+
+//one dice pair, or one unopposed die when the other side is None
+#[derive(Clone)]
+pub struct ClashRecord {
+    pub own: Option<Dice>,
+    pub other: Option<Dice>,
+    pub winner: Winner,
+    pub kind: ClashKind,
+    pub own_damage: Damage,
+    pub other_damage: Damage,
+    //how the damage above actually landed, filled in once applied
+    pub own_breakdown: HitBreakdown,
+    pub other_breakdown: HitBreakdown,
+    //the signed result die `own - other` this record was read from
+    pub result: Dice,
+}
+
+impl ClashRecord {
+    pub fn new(own: Option<Dice>, other: Option<Dice>, result: Dice) -> Self {
+        let winner = result.winner();
+        let value = result.cur().abs();
+        let mut winner_damage = Damage::default();
+        let mut loser_damage = Damage::default();
+        let inner = match result.dtype() {
+            DiceType::Combat(CombatDice::Normal(dice))
+            | DiceType::Combat(CombatDice::Counter(dice)) => Some(dice),
+            DiceType::Speed => None,
+        };
+        let kind = match (winner, inner) {
+            (Winner::Draw, _) | (_, None) => ClashKind::Draw,
+            (_, Some(NormalDice::Attack(atype))) => {
+                loser_damage.attack = Some(atype.clone());
+                loser_damage.health = value;
+                loser_damage.stagger = value;
+                ClashKind::Hit
+            }
+            (_, Some(NormalDice::Defense(DefenseDice::Block))) => {
+                loser_damage.stagger = value;
+                ClashKind::Block
+            }
+            (_, Some(NormalDice::Defense(DefenseDice::Evade))) => {
+                winner_damage.recovery = value;
+                ClashKind::Evade
+            }
+        };
+        let (own_damage, other_damage) = match winner {
+            Winner::Other => (loser_damage, winner_damage),
+            _ => (winner_damage, loser_damage),
+        };
+
+        Self {
+            own,
+            other,
+            winner,
+            kind,
+            own_damage,
+            other_damage,
+            own_breakdown: HitBreakdown::default(),
+            other_breakdown: HitBreakdown::default(),
+            result,
+        }
+    }
+
+    pub fn own_value(&self) -> Option<i32> {
+        self.own.as_ref().map(|x| x.cur())
+    }
+
+    pub fn other_value(&self) -> Option<i32> {
+        self.other.as_ref().map(|x| x.cur())
+    }
+
+    //no opposing die, the record is a one-sided hit
+    pub fn is_unopposed(&self) -> bool {
+        self.own.is_none() || self.other.is_none()
+    }
+
+    pub fn winning_dice(&self) -> Option<&Dice> {
+        match self.winner {
+            Winner::Own => self.own.as_ref(),
+            Winner::Other => self.other.as_ref(),
+            Winner::Draw => None,
+        }
+    }
+
+    pub fn losing_dice(&self) -> Option<&Dice> {
+        match self.winner {
+            Winner::Own => self.other.as_ref(),
+            Winner::Other => self.own.as_ref(),
+            Winner::Draw => None,
+        }
+    }
+}
+
+//records in clash order followed by one-sided hits, plus each side's
+//dice that were never used
+#[derive(Clone, Default)]
+pub struct ClashReport {
+    pub records: Vec<ClashRecord>,
+    pub own_unused: Vec<Dice>,
+    pub other_unused: Vec<Dice>,
+}
+
+impl ClashReport {
+    pub fn wins(&self, winner: Winner) -> usize {
+        self.records.iter().filter(|x| x.winner == winner).count()
+    }
+
+    //settle every record on both key pages, without firing any effect;
+    //damage goes through the same outgoing and incoming layers as a clash
+    pub fn apply(&self, own: &mut KeyPage, other: &mut KeyPage) {
+        let own_out = own.outgoing + own.statuses.outgoing();
+        let other_out = other.outgoing + other.statuses.outgoing();
+        for record in &self.records {
+            own.take_from(&record.own_damage, &other_out);
+            other.take_from(&record.other_damage, &own_out);
+        }
+    }
+
+    //the (result, self, other) triple CombatPage::eval hands out
+    pub fn into_evaluation(self) -> Evaluation {
+        (
+            self.records.into_iter().map(|x| x.result).collect(),
+            self.own_unused,
+            self.other_unused,
+        )
+    }
+}
+
+//clash two pages and apply the outcome to whichever key page it hurts:
+//hits, block stagger damage and evade recovery all land on the right side
+pub fn resolve<R: Rng + ?Sized>(
+    own: &mut KeyPage,
+    own_page: CombatPage,
+    other: &mut KeyPage,
+    other_page: CombatPage,
+    rng: &mut R,
+) -> ClashReport {
+    own_page.clash(other_page, own, other, rng)
+}
+
+pub fn try_resolve<R: Rng + ?Sized>(
+    own: &mut KeyPage,
+    own_page: CombatPage,
+    other: &mut KeyPage,
+    other_page: CombatPage,
+    rng: &mut R,
+) -> Result<ClashReport, CombatError> {
+    own_page.try_clash(other_page, own, other, rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::damage::Stage;
+    use crate::object::{CombatPageBuilder, KeyPageBuilder, Resistance};
+
+    fn dice(dtype: NormalDice, value: i32) -> Dice {
+        Dice::new(DiceType::Combat(CombatDice::Normal(dtype)), value, value)
+    }
+
+    #[test]
+    fn record_kinds() {
+        let own = CombatPageBuilder::new()
+            .dice(dice(NormalDice::Attack(AttackDice::Slash), 5))
+            .dice(dice(NormalDice::Attack(AttackDice::Pierce), 2))
+            .dice(dice(NormalDice::Defense(DefenseDice::Evade), 6))
+            .dice(dice(NormalDice::Attack(AttackDice::Blunt), 3))
+            .dice(dice(NormalDice::Attack(AttackDice::Blunt), 4))
+            .build();
+        let other = CombatPageBuilder::new()
+            .dice(dice(NormalDice::Defense(DefenseDice::Block), 3))
+            .dice(dice(NormalDice::Defense(DefenseDice::Block), 7))
+            .dice(dice(NormalDice::Attack(AttackDice::Slash), 4))
+            .dice(dice(NormalDice::Attack(AttackDice::Slash), 3))
+            .build();
+        let report = own.resolve_with(other, &mut crate::rng::seeded(0));
+        let records = &report.records;
+        assert_eq!(records.len(), 5);
+
+        assert_eq!(records[0].kind, ClashKind::Hit);
+        assert_eq!(records[0].winner, Winner::Own);
+        assert_eq!(records[0].other_damage.health, 2);
+        assert_eq!(records[0].other_damage.attack, Some(AttackDice::Slash));
+        assert!(records[0].own_damage.is_empty());
+
+        assert_eq!(records[1].kind, ClashKind::Block);
+        assert_eq!(records[1].winner, Winner::Other);
+        assert_eq!(records[1].own_damage.stagger, 5);
+        assert_eq!(records[1].own_damage.health, 0);
+
+        assert_eq!(records[2].kind, ClashKind::Evade);
+        assert_eq!(records[2].own_damage.recovery, 6);
+        assert_eq!(records[2].own_value(), Some(6));
+        assert_eq!(records[2].other_value(), Some(4));
+
+        assert_eq!(records[3].kind, ClashKind::Draw);
+        assert!(records[3].own_damage.is_empty() && records[3].other_damage.is_empty());
+
+        assert!(records[4].is_unopposed());
+        assert_eq!(records[4].kind, ClashKind::Hit);
+        assert_eq!(records[4].other_damage.health, 4);
+        assert_eq!(report.wins(Winner::Own), 3);
+    }
+
+    #[test]
+    fn resolve_both_sides() {
+        let own_page = CombatPageBuilder::new()
+            .dice(dice(NormalDice::Attack(AttackDice::Slash), 5))
+            .dice(dice(NormalDice::Defense(DefenseDice::Block), 2))
+            .dice(dice(NormalDice::Defense(DefenseDice::Evade), 6))
+            .build();
+        let other_page = CombatPageBuilder::new()
+            .dice(dice(NormalDice::Attack(AttackDice::Pierce), 3))
+            .dice(dice(NormalDice::Attack(AttackDice::Blunt), 7))
+            .dice(dice(NormalDice::Attack(AttackDice::Blunt), 1))
+            .build();
+        let mut own = KeyPageBuilder::new()
+            .health(50)
+            .stagger(20)
+            .hblunt_resistance(Resistance::Weak)
+            .build();
+        let mut other = KeyPageBuilder::new()
+            .health(50)
+            .stagger(20)
+            .sslash_resistance(Resistance::Fatal)
+            .build();
+        own.curstagger = 15;
+
+        let report = resolve(
+            &mut own,
+            own_page,
+            &mut other,
+            other_page,
+            &mut crate::rng::seeded(0),
+        );
+        assert_eq!(report.records.len(), 3);
+        //slash 5 beats pierce 3 for a full hit, fatal stagger doubles it
+        assert_eq!(other.health(), 45);
+        assert_eq!(other.stagger(), 10);
+        //blunt 7 breaks through block 2 for 5, weak health makes it 7
+        assert_eq!(own.health(), 43);
+        //15 - 5 stagger from the blunt hit, + 6 from the evade, capped at 20
+        assert_eq!(own.stagger(), 16);
+    }
+
+    #[test]
+    fn resolve_breakdown() {
+        let own_page = CombatPageBuilder::new()
+            .dice(dice(NormalDice::Attack(AttackDice::Pierce), 6))
+            .build();
+        let mut own = KeyPageBuilder::new().health(50).stagger(50).build();
+        let mut other = KeyPageBuilder::new()
+            .health(50)
+            .stagger(50)
+            .hpierce_resistance(Resistance::Endured)
+            .build();
+        own.outgoing.health.bonus = 2;
+        other.incoming.health.reduction = 1;
+
+        let fresh = other.clone();
+        let report = resolve(
+            &mut own,
+            own_page.clone(),
+            &mut other,
+            CombatPage::default(),
+            &mut crate::rng::seeded(0),
+        );
+        let health = &report.records[0].other_breakdown.health;
+        assert_eq!(health.get(Stage::Bonus), Some(8.0));
+        assert_eq!(health.get(Stage::Resistance), Some(4.0));
+        assert_eq!(health.value, 3);
+        assert_eq!(report.records[0].other_breakdown.stagger.value, 6);
+        assert_eq!(other.health(), 47);
+        assert_eq!(other.stagger(), 44);
+
+        //a report settled later lands the same damage
+        let mut other = fresh;
+        own_page
+            .resolve_with(CombatPage::default(), &mut crate::rng::seeded(0))
+            .apply(&mut own, &mut other);
+        assert_eq!(other.health(), 47);
+        assert_eq!(other.stagger(), 44);
+    }
+}
@@ -0,0 +1,208 @@
+// damage pipeline of LOR combat
+
+use std::ops::Add;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rounding {
+    //toward zero, what the game does
+    #[default]
+    Truncate,
+    Floor,
+    Ceil,
+    Round,
+}
+
+impl Rounding {
+    pub fn apply(&self, value: f32) -> i32 {
+        match self {
+            Self::Truncate => value.trunc() as i32,
+            Self::Floor => value.floor() as i32,
+            Self::Ceil => value.ceil() as i32,
+            Self::Round => value.round() as i32,
+        }
+    }
+}
+
+//stages in the order a hit goes through them
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Base,
+    Bonus,
+    Multiplier,
+    Resistance,
+    Reduction,
+    Clamp,
+    Rounding,
+}
+
+//flat and percentage layers one side adds to a hit
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DamageModifier {
+    //added to the die value before multipliers
+    pub bonus: i32,
+    //additive percent, +50 is x1.5 and -25 is x0.75
+    pub percent: i32,
+    //subtracted after resistance, e.g. protection
+    pub reduction: i32,
+}
+
+impl Add for DamageModifier {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            bonus: self.bonus + other.bonus,
+            percent: self.percent + other.percent,
+            reduction: self.reduction + other.reduction,
+        }
+    }
+}
+
+//health and stagger are modified separately
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DamageModifiers {
+    pub health: DamageModifier,
+    pub stagger: DamageModifier,
+}
+
+impl DamageModifiers {
+    //per stack layers of a status holding `stacks`
+    pub fn times(&self, stacks: i32) -> Self {
+        let times = |x: DamageModifier| DamageModifier {
+            bonus: x.bonus * stacks,
+            percent: x.percent * stacks,
+            reduction: x.reduction * stacks,
+        };
+        Self {
+            health: times(self.health),
+            stagger: times(self.stagger),
+        }
+    }
+}
+
+impl Add for DamageModifiers {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            health: self.health + other.health,
+            stagger: self.stagger + other.stagger,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DamagePipeline {
+    pub min: i32,
+    pub max: Option<i32>,
+    pub rounding: Rounding,
+}
+
+impl Default for DamagePipeline {
+    fn default() -> Self {
+        Self {
+            min: 0,
+            max: None,
+            rounding: Rounding::Truncate,
+        }
+    }
+}
+
+impl DamagePipeline {
+    pub fn run(&self, base: i32, modifier: &DamageModifier, resistance: f32) -> Breakdown {
+        let mut breakdown = Breakdown::default();
+        let mut value = base as f32;
+        breakdown.push(Stage::Base, value);
+
+        value += modifier.bonus as f32;
+        breakdown.push(Stage::Bonus, value);
+
+        value *= 1.0 + modifier.percent as f32 / 100.0;
+        breakdown.push(Stage::Multiplier, value);
+
+        value *= resistance;
+        breakdown.push(Stage::Resistance, value);
+
+        value -= modifier.reduction as f32;
+        breakdown.push(Stage::Reduction, value);
+
+        value = value.max(self.min as f32);
+        if let Some(max) = self.max {
+            value = value.min(max as f32);
+        }
+        breakdown.push(Stage::Clamp, value);
+
+        breakdown.value = self.rounding.apply(value);
+        breakdown.push(Stage::Rounding, breakdown.value as f32);
+        breakdown
+    }
+}
+
+//value after every stage of one hit, for whoever wants to know why
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Breakdown {
+    pub steps: Vec<(Stage, f32)>,
+    pub value: i32,
+}
+
+impl Breakdown {
+    fn push(&mut self, stage: Stage, value: f32) {
+        self.steps.push((stage, value));
+    }
+
+    pub fn get(&self, stage: Stage) -> Option<f32> {
+        self.steps
+            .iter()
+            .find(|(x, _)| *x == stage)
+            .map(|(_, y)| *y)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HitBreakdown {
+    pub health: Breakdown,
+    pub stagger: Breakdown,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pipeline_stages() {
+        let pipeline = DamagePipeline::default();
+        let modifier = DamageModifier {
+            bonus: 2,
+            percent: 50,
+            reduction: 3,
+        };
+        let breakdown = pipeline.run(5, &modifier, 1.5);
+        assert_eq!(breakdown.get(Stage::Base), Some(5.0));
+        assert_eq!(breakdown.get(Stage::Bonus), Some(7.0));
+        assert_eq!(breakdown.get(Stage::Multiplier), Some(10.5));
+        assert_eq!(breakdown.get(Stage::Resistance), Some(15.75));
+        assert_eq!(breakdown.get(Stage::Reduction), Some(12.75));
+        assert_eq!(breakdown.value, 12);
+        assert_eq!(breakdown.steps.len(), 7);
+    }
+
+    #[test]
+    fn pipeline_clamp_rounding() {
+        let modifier = DamageModifier {
+            reduction: 10,
+            ..Default::default()
+        };
+        assert_eq!(DamagePipeline::default().run(4, &modifier, 1.0).value, 0);
+
+        let pipeline = DamagePipeline {
+            min: 1,
+            max: Some(20),
+            rounding: Rounding::Ceil,
+        };
+        assert_eq!(pipeline.run(4, &modifier, 1.0).value, 1);
+        assert_eq!(pipeline.run(15, &DamageModifier::default(), 2.0).value, 20);
+        assert_eq!(pipeline.run(5, &DamageModifier::default(), 0.5).value, 3);
+        assert_eq!(Rounding::Round.apply(2.5), 3);
+        assert_eq!(Rounding::Floor.apply(2.5), 2);
+    }
+}
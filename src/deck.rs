@@ -0,0 +1,296 @@
+// decks and hands of LOR combat
+
+use crate::error::CombatError;
+use crate::object::{CombatPage, KeyPage, PageRarity};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+
+pub const DECK_SIZE: usize = 9;
+//pages drawn when the act starts, one more is drawn every scene after
+pub const OPENING_HAND: usize = 4;
+pub const SCENE_DRAW: usize = 1;
+pub const MAX_COPIES: usize = 3;
+//limited and art pages
+pub const MAX_UNIQUE_COPIES: usize = 1;
+
+//the nine pages a combatant brings into an act
+#[derive(Clone, Default)]
+pub struct Deck {
+    pages: Vec<CombatPage>,
+}
+
+impl Deck {
+    pub fn new(pages: Vec<CombatPage>) -> Self {
+        Self::try_new(pages).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_new(pages: Vec<CombatPage>) -> Result<Self, CombatError> {
+        if pages.len() != DECK_SIZE {
+            return Err(CombatError::DeckSize(pages.len()));
+        }
+        Ok(Self { pages })
+    }
+
+    //a deck that passes every building rule for `key`
+    pub fn try_for(pages: Vec<CombatPage>, key: &KeyPage) -> Result<Self, Vec<DeckViolation>> {
+        validate(&pages, key)?;
+        Ok(Self { pages })
+    }
+
+    pub fn pages(&self) -> &Vec<CombatPage> {
+        &self.pages
+    }
+}
+
+//one broken deck building rule, pages are named so an editor can point
+//at them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeckViolation {
+    Size(usize),
+    //(page, copies)
+    Copies(String, usize),
+    UniqueCopies(String, usize),
+    //(page, key page it belongs to)
+    Exclusive(String, String),
+}
+
+impl fmt::Display for DeckViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Size(x) => write!(f, "deck must hold {} pages, got {}", DECK_SIZE, x),
+            Self::Copies(name, x) => {
+                write!(f, "{}: {} copies, at most {}", name, x, MAX_COPIES)
+            }
+            Self::UniqueCopies(name, x) => write!(
+                f,
+                "{}: {} copies of a limited page, at most {}",
+                name, x, MAX_UNIQUE_COPIES
+            ),
+            Self::Exclusive(name, key) => write!(f, "{}: only {} can use this page", name, key),
+        }
+    }
+}
+
+//every rule `pages` breaks as the deck of `key`, not just the first;
+//copies are counted by page name
+pub fn validate(pages: &[CombatPage], key: &KeyPage) -> Result<(), Vec<DeckViolation>> {
+    let mut violations = Vec::new();
+    if pages.len() != DECK_SIZE {
+        violations.push(DeckViolation::Size(pages.len()));
+    }
+    for (index, page) in pages.iter().enumerate() {
+        //each distinct page is checked once, at its first copy
+        if pages[..index].iter().any(|x| x.name() == page.name()) {
+            continue;
+        }
+        let copies = pages.iter().filter(|x| x.name() == page.name()).count();
+        let name = page.name().to_string();
+        match page.rarity() {
+            PageRarity::Limited | PageRarity::Art if copies > MAX_UNIQUE_COPIES => {
+                violations.push(DeckViolation::UniqueCopies(name.clone(), copies));
+            }
+            _ if copies > MAX_COPIES => {
+                violations.push(DeckViolation::Copies(name.clone(), copies));
+            }
+            _ => {}
+        }
+        if let Some(owner) = &page.exclusive {
+            if *owner != key.name {
+                violations.push(DeckViolation::Exclusive(name, owner.clone()));
+            }
+        }
+    }
+    if violations.is_empty() {
+        return Ok(());
+    }
+    Err(violations)
+}
+
+//a deck in play: draw pile, hand and the used pile pages go to once played
+//or discarded; the top of the draw pile is its last page
+#[derive(Clone, Default)]
+pub struct Hand {
+    pub draw: Vec<CombatPage>,
+    pub pages: Vec<CombatPage>,
+    pub discard: Vec<CombatPage>,
+}
+
+impl Hand {
+    pub fn new(deck: &Deck) -> Self {
+        Self {
+            draw: deck.pages.clone(),
+            ..Default::default()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&CombatPage> {
+        self.pages.get(index)
+    }
+
+    //shuffle the draw pile and draw the opening hand
+    pub fn open_with<R: Rng + ?Sized>(&mut self, rng: &mut R) -> usize {
+        self.draw.shuffle(rng);
+        self.draw_with(OPENING_HAND, rng)
+    }
+
+    //an exhausted draw pile takes back the used pile, shuffled; returns the
+    //pages actually drawn, fewer once both piles run dry
+    pub fn draw_with<R: Rng + ?Sized>(&mut self, amount: usize, rng: &mut R) -> usize {
+        for drawn in 0..amount {
+            if self.draw.is_empty() {
+                self.draw.append(&mut self.discard);
+                self.draw.shuffle(rng);
+            }
+            match self.draw.pop() {
+                Some(page) => self.pages.push(page),
+                None => return drawn,
+            }
+        }
+        amount
+    }
+
+    //take a page out of the hand to put on a speed die, it goes to the
+    //used pile
+    pub fn play(&mut self, index: usize) -> Option<CombatPage> {
+        if index >= self.pages.len() {
+            return None;
+        }
+        let page = self.pages.remove(index);
+        self.discard.push(page.clone());
+        Some(page)
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.pages.iter().position(|x| x.name() == name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.position(name).is_some()
+    }
+
+    //pages only move between the piles, so all three are empty only for a
+    //unit built without a deck
+    pub fn is_deckless(&self) -> bool {
+        self.draw.is_empty() && self.pages.is_empty() && self.discard.is_empty()
+    }
+
+    //play by name, for pages handed to the engine directly
+    pub fn play_named(&mut self, name: &str) -> Option<CombatPage> {
+        self.play(self.position(name)?)
+    }
+
+    pub fn discard(&mut self, name: &str) -> bool {
+        match self.position(name) {
+            Some(index) => {
+                let page = self.pages.remove(index);
+                self.discard.push(page);
+                true
+            }
+            None => false,
+        }
+    }
+
+    //bring a page back to the hand, from the used pile first
+    pub fn retrieve(&mut self, name: &str) -> bool {
+        for pile in [&mut self.discard, &mut self.draw] {
+            if let Some(index) = pile.iter().position(|x| x.name() == name) {
+                let page = pile.remove(index);
+                self.pages.push(page);
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{CombatPageBuilder, KeyPageBuilder};
+
+    fn deck() -> Deck {
+        Deck::new(
+            (0..DECK_SIZE)
+                .map(|x| CombatPageBuilder::new().name(&x.to_string()).build())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn deck_size() {
+        assert_eq!(
+            Deck::try_new(Vec::new()).err().map(|x| x.to_string()),
+            Some(CombatError::DeckSize(0).to_string())
+        );
+        assert_eq!(deck().pages().len(), DECK_SIZE);
+    }
+
+    #[test]
+    fn hand_draw_and_reshuffle() {
+        let mut rng = crate::rng::seeded(0);
+        let mut hand = Hand::new(&deck());
+        assert_eq!(hand.open_with(&mut rng), OPENING_HAND);
+        assert_eq!(hand.draw.len(), DECK_SIZE - OPENING_HAND);
+
+        let name = hand.get(0).unwrap().name().to_string();
+        assert_eq!(hand.play(0).unwrap().name(), name);
+        assert!(hand.retrieve(&name));
+        assert!(hand.discard(&name));
+        assert!(!hand.discard("missing"));
+
+        //5 left in the pile, the 6th draw takes the used page back
+        assert_eq!(hand.draw_with(6, &mut rng), 6);
+        assert!(hand.discard.is_empty());
+        assert_eq!(hand.len(), DECK_SIZE);
+        assert_eq!(hand.draw_with(1, &mut rng), 0);
+    }
+
+    #[test]
+    fn deck_violations() {
+        let key = KeyPageBuilder::new().name("Roland").build();
+        let page = |name: &str, rarity| CombatPageBuilder::new().name(name).rarity(rarity).build();
+        let mut pages = vec![page("Evade", PageRarity::Paperback); 4];
+        pages.extend(vec![page("Furioso", PageRarity::Art); 2]);
+        pages.push(
+            CombatPageBuilder::new()
+                .name("Zelkova")
+                .exclusive("Angela")
+                .build(),
+        );
+        pages.push(
+            CombatPageBuilder::new()
+                .name("Durandal")
+                .exclusive("Roland")
+                .build(),
+        );
+
+        let violations = validate(&pages, &key).unwrap_err();
+        assert_eq!(
+            violations,
+            vec![
+                DeckViolation::Size(8),
+                DeckViolation::Copies("Evade".to_string(), 4),
+                DeckViolation::UniqueCopies("Furioso".to_string(), 2),
+                DeckViolation::Exclusive("Zelkova".to_string(), "Angela".to_string()),
+            ]
+        );
+        assert_eq!(
+            violations[3].to_string(),
+            "Zelkova: only Angela can use this page"
+        );
+
+        pages.retain(|x| x.name() != "Zelkova");
+        pages.truncate(3);
+        pages.extend((0..6).map(|x| page(&x.to_string(), PageRarity::Limited)));
+        assert!(Deck::try_for(pages, &key).is_ok());
+    }
+}
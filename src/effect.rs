@@ -0,0 +1,365 @@
+// dice and page effects of LOR combat
+
+use crate::object::{Dice, KeyPage};
+use crate::status::StatusRef;
+use std::fmt;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    OnUse,
+    OnHit,
+    ClashWin,
+    ClashLose,
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OnUse => write!(f, "[On Use]"),
+            Self::OnHit => write!(f, "[On Hit]"),
+            Self::ClashWin => write!(f, "[Clash Win]"),
+            Self::ClashLose => write!(f, "[Clash Lose]"),
+        }
+    }
+}
+
+//state an effect sees when it fires, owner is the side whose die or page fired
+pub struct EffectContext<'a> {
+    pub owner: &'a mut KeyPage,
+    pub target: &'a mut KeyPage,
+    pub dice: Option<&'a Dice>,
+    pub opponent: Option<&'a Dice>,
+}
+
+pub trait DiceEffect: Send + Sync {
+    fn trigger(&self) -> Trigger;
+
+    fn fire(&self, ctx: &mut EffectContext);
+
+    //effect text without the trigger, e.g. "Recover 3 Stagger"
+    fn describe(&self) -> String;
+}
+
+pub type Effect = Arc<dyn DiceEffect>;
+
+//"[Clash Win] Recover 3 Stagger"
+pub fn text(effect: &dyn DiceEffect) -> String {
+    format!("{} {}", effect.trigger(), effect.describe())
+}
+
+pub fn fire(effects: &[Effect], trigger: Trigger, ctx: &mut EffectContext) {
+    effects
+        .iter()
+        .filter(|x| x.trigger() == trigger)
+        .for_each(|x| x.fire(ctx));
+}
+
+pub struct RecoverHealth {
+    pub trigger: Trigger,
+    pub amount: i32,
+}
+
+impl DiceEffect for RecoverHealth {
+    fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    fn fire(&self, ctx: &mut EffectContext) {
+        let owner = &mut *ctx.owner;
+        owner.curhealth = (owner.curhealth + self.amount).min(owner.maxhealth);
+    }
+
+    fn describe(&self) -> String {
+        format!("Recover {} HP", self.amount)
+    }
+}
+
+pub struct RecoverStagger {
+    pub trigger: Trigger,
+    pub amount: i32,
+}
+
+impl DiceEffect for RecoverStagger {
+    fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    fn fire(&self, ctx: &mut EffectContext) {
+        let owner = &mut *ctx.owner;
+        owner.curstagger = (owner.curstagger + self.amount).min(owner.maxstagger);
+    }
+
+    fn describe(&self) -> String {
+        format!("Recover {} Stagger", self.amount)
+    }
+}
+
+pub struct RestoreLight {
+    pub trigger: Trigger,
+    pub amount: i32,
+}
+
+impl DiceEffect for RestoreLight {
+    fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    fn fire(&self, ctx: &mut EffectContext) {
+        ctx.owner.restore_light(self.amount);
+    }
+
+    fn describe(&self) -> String {
+        format!("Restore {} Light", self.amount)
+    }
+}
+
+//raises max light for the rest of the act, the new light comes filled
+pub struct GainLight {
+    pub trigger: Trigger,
+    pub amount: i32,
+}
+
+impl DiceEffect for GainLight {
+    fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    fn fire(&self, ctx: &mut EffectContext) {
+        ctx.owner.maxlights += self.amount;
+        ctx.owner.curlights += self.amount;
+    }
+
+    fn describe(&self) -> String {
+        format!("Gain {} extra Light", self.amount)
+    }
+}
+
+//queued on the owner, drawn once the engine settles it with its rng
+pub struct DrawPages {
+    pub trigger: Trigger,
+    pub amount: i32,
+}
+
+impl DiceEffect for DrawPages {
+    fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    fn fire(&self, ctx: &mut EffectContext) {
+        ctx.owner.draws += self.amount;
+    }
+
+    fn describe(&self) -> String {
+        match self.amount {
+            1 => "Draw 1 page".to_string(),
+            n => format!("Draw {} pages", n),
+        }
+    }
+}
+
+//stacks of a status or resource for the owner, active right away
+pub struct GainStatus {
+    pub trigger: Trigger,
+    pub status: StatusRef,
+    pub stacks: i32,
+}
+
+impl DiceEffect for GainStatus {
+    fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    fn fire(&self, ctx: &mut EffectContext) {
+        ctx.owner.statuses.add(self.status.clone(), self.stacks);
+    }
+
+    fn describe(&self) -> String {
+        format!("Gain {} {}", self.stacks, self.status.name())
+    }
+}
+
+//stacks of a status for the target, active from the next scene
+pub struct InflictStatus {
+    pub trigger: Trigger,
+    pub status: StatusRef,
+    pub stacks: i32,
+}
+
+impl DiceEffect for InflictStatus {
+    fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    fn fire(&self, ctx: &mut EffectContext) {
+        ctx.target
+            .statuses
+            .add_next(self.status.clone(), self.stacks);
+    }
+
+    fn describe(&self) -> String {
+        format!("Inflict {} {} next scene", self.stacks, self.status.name())
+    }
+}
+
+pub struct DiscardPage {
+    pub trigger: Trigger,
+    pub name: String,
+}
+
+impl DiceEffect for DiscardPage {
+    fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    fn fire(&self, ctx: &mut EffectContext) {
+        ctx.owner.hand.discard(&self.name);
+    }
+
+    fn describe(&self) -> String {
+        format!("Discard {}", self.name)
+    }
+}
+
+//bring a named page back to the owner's hand from the used or draw pile
+pub struct RetrievePage {
+    pub trigger: Trigger,
+    pub name: String,
+}
+
+impl DiceEffect for RetrievePage {
+    fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    fn fire(&self, ctx: &mut EffectContext) {
+        ctx.owner.hand.retrieve(&self.name);
+    }
+
+    fn describe(&self) -> String {
+        format!("Retrieve {}", self.name)
+    }
+}
+
+//one-off effect written as a closure
+pub struct FnEffect<F> {
+    trigger: Trigger,
+    text: String,
+    f: F,
+}
+
+impl<F> DiceEffect for FnEffect<F>
+where
+    F: Fn(&mut EffectContext) + Send + Sync,
+{
+    fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    fn fire(&self, ctx: &mut EffectContext) {
+        (self.f)(ctx)
+    }
+
+    fn describe(&self) -> String {
+        self.text.clone()
+    }
+}
+
+pub fn effect<F>(trigger: Trigger, text: &str, f: F) -> Effect
+where
+    F: Fn(&mut EffectContext) + Send + Sync + 'static,
+{
+    Arc::new(FnEffect {
+        trigger,
+        text: text.to_string(),
+        f,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::key;
+    use crate::object::{
+        AttackDice, CombatDice, CombatPageBuilder, DefenseDice, DiceType, NormalDice,
+    };
+
+    #[test]
+    fn effect_text() {
+        let recover = RecoverStagger {
+            trigger: Trigger::ClashWin,
+            amount: 3,
+        };
+        assert_eq!(text(&recover), "[Clash Win] Recover 3 Stagger");
+        let draw = DrawPages {
+            trigger: Trigger::OnUse,
+            amount: 1,
+        };
+        assert_eq!(text(&draw), "[On Use] Draw 1 page");
+        let light = GainLight {
+            trigger: Trigger::ClashWin,
+            amount: 1,
+        };
+        assert_eq!(text(&light), "[Clash Win] Gain 1 extra Light");
+        let retrieve = RetrievePage {
+            trigger: Trigger::OnUse,
+            name: "Evade".to_string(),
+        };
+        assert_eq!(text(&retrieve), "[On Use] Retrieve Evade");
+        let bleed = InflictStatus {
+            trigger: Trigger::OnHit,
+            status: Arc::new(crate::status::Bleed),
+            stacks: 2,
+        };
+        assert_eq!(text(&bleed), "[On Hit] Inflict 2 Bleed next scene");
+    }
+
+    #[test]
+    fn effect_clash_triggers() {
+        let win = Dice::new(
+            DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Slash))),
+            5,
+            5,
+        )
+        .with_effect(Arc::new(RecoverStagger {
+            trigger: Trigger::ClashWin,
+            amount: 3,
+        }))
+        .with_effect(effect(Trigger::OnHit, "Deal 4 HP damage", |ctx| {
+            ctx.target.curhealth -= 4;
+        }));
+        let lose = Dice::new(
+            DiceType::Combat(CombatDice::Normal(NormalDice::Defense(DefenseDice::Block))),
+            1,
+            1,
+        )
+        .with_effect(Arc::new(RecoverHealth {
+            trigger: Trigger::ClashLose,
+            amount: 5,
+        }))
+        .with_effect(Arc::new(RecoverHealth {
+            trigger: Trigger::ClashWin,
+            amount: 100,
+        }));
+        let own_page = CombatPageBuilder::new()
+            .dice(win)
+            .effect(Arc::new(DrawPages {
+                trigger: Trigger::OnUse,
+                amount: 1,
+            }))
+            .build();
+        let other_page = CombatPageBuilder::new().dice(lose).build();
+
+        let mut own = key();
+        let mut other = key();
+        own.curstagger = 10;
+        other.curhealth = 40;
+        let report = own_page.clash(other_page, &mut own, &mut other, &mut crate::rng::seeded(0));
+
+        assert_eq!(report.records.len(), 1);
+        assert_eq!(own.stagger(), 13);
+        assert_eq!(own.draws, 1);
+        //40 - 4 from the hit - 4 from the effect + 5 on losing
+        assert_eq!(other.health(), 37);
+    }
+}
@@ -0,0 +1,172 @@
+// emotion levels of LOR combat
+
+use crate::clash::{ClashKind, ClashRecord};
+use crate::object::{KeyPage, Winner};
+
+pub const MAX_LEVEL: u32 = 5;
+//coins needed to leave each level below MAX_LEVEL
+pub const THRESHOLDS: [u32; MAX_LEVEL as usize] = [3, 3, 5, 7, 9];
+//levels that add a speed die
+pub const SPEED_LEVELS: [u32; 1] = [4];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CoinSource {
+    ClashWin,
+    ClashLose,
+    DealDamage,
+    TakeDamage,
+}
+
+impl CoinSource {
+    pub fn is_positive(&self) -> bool {
+        matches!(self, Self::ClashWin | Self::DealDamage)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Emotion {
+    pub level: u32,
+    //counted toward the next level, positive and negative alike
+    pub coins: u32,
+    pub positive: u32,
+    pub negative: u32,
+    //coins since the last level check, in the order they came
+    pub gained: Vec<CoinSource>,
+}
+
+impl Emotion {
+    pub fn gain(&mut self, source: CoinSource) {
+        if source.is_positive() {
+            self.positive += 1;
+        } else {
+            self.negative += 1;
+        }
+        self.gained.push(source);
+    }
+
+    //coins the current level needs, None at MAX_LEVEL
+    pub fn threshold(&self) -> Option<u32> {
+        THRESHOLDS.get(self.level as usize).copied()
+    }
+}
+
+//coins of one clash record: winning and losing the clash, then dealing and
+//taking health damage
+pub(crate) fn record(own: &mut KeyPage, other: &mut KeyPage, record: &ClashRecord) {
+    if !record.is_unopposed() {
+        match record.winner {
+            Winner::Own => {
+                own.emotion.gain(CoinSource::ClashWin);
+                other.emotion.gain(CoinSource::ClashLose);
+            }
+            Winner::Other => {
+                other.emotion.gain(CoinSource::ClashWin);
+                own.emotion.gain(CoinSource::ClashLose);
+            }
+            Winner::Draw => {}
+        }
+    }
+    if record.kind != ClashKind::Hit {
+        return;
+    }
+    if record.other_breakdown.health.value > 0 {
+        own.emotion.gain(CoinSource::DealDamage);
+        other.emotion.gain(CoinSource::TakeDamage);
+    }
+    if record.own_breakdown.health.value > 0 {
+        other.emotion.gain(CoinSource::DealDamage);
+        own.emotion.gain(CoinSource::TakeDamage);
+    }
+}
+
+//count the coins gained so far and raise the level as far as they reach;
+//every level adds 1 max light and refills light, SPEED_LEVELS add a copy
+//of the first speed die. returns the levels gained
+pub(crate) fn level_up(key: &mut KeyPage) -> u32 {
+    let gained = key.emotion.gained.len() as u32;
+    key.emotion.gained.clear();
+    key.emotion.coins += gained;
+    let mut levels = 0;
+    while let Some(threshold) = key.emotion.threshold() {
+        if key.emotion.coins < threshold {
+            break;
+        }
+        key.emotion.coins -= threshold;
+        key.emotion.level += 1;
+        levels += 1;
+        key.maxlights += 1;
+        key.curlights = key.maxlights;
+        let slot = key.reserved_slot();
+        if SPEED_LEVELS.contains(&key.emotion.level) && slot > 0 {
+            let dice = key.speed[0].clone();
+            key.speed.insert(slot, dice);
+        }
+    }
+    if key.emotion.threshold().is_none() {
+        key.emotion.coins = 0;
+    }
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::slash_page;
+    use crate::object::{Dice, DiceType, KeyPageBuilder};
+
+    #[test]
+    fn emotion_coins() {
+        let mut own = KeyPageBuilder::new().health(50).stagger(50).build();
+        let mut other = KeyPageBuilder::new().health(50).stagger(50).build();
+        let _ = slash_page(5).clash(
+            slash_page(2),
+            &mut own,
+            &mut other,
+            &mut crate::rng::seeded(0),
+        );
+        assert_eq!(
+            own.emotion.gained,
+            vec![CoinSource::ClashWin, CoinSource::DealDamage]
+        );
+        assert_eq!(
+            other.emotion.gained,
+            vec![CoinSource::ClashLose, CoinSource::TakeDamage]
+        );
+        assert_eq!((other.emotion.positive, other.emotion.negative), (0, 2));
+
+        //one-sided hits give no clash coins
+        let _ = slash_page(3).one_sided(&mut own, &mut other, &mut crate::rng::seeded(0));
+        assert_eq!(own.emotion.gained.len(), 3);
+        assert_eq!(own.level_up(), 1);
+        assert_eq!(own.emotion.coins, 0);
+        assert!(own.emotion.gained.is_empty());
+    }
+
+    #[test]
+    fn emotion_levels() {
+        let mut key = KeyPageBuilder::new()
+            .lights(3)
+            .speed(Dice::new(DiceType::Speed, 1, 4))
+            .build();
+        key.curlights = 0;
+        for _ in 0..(3 + 3 + 5 + 7 + 1) {
+            key.emotion.gain(CoinSource::TakeDamage);
+        }
+        assert_eq!(key.level_up(), 4);
+        assert_eq!(key.emotion.level, 4);
+        assert_eq!(key.emotion.coins, 1);
+        assert_eq!((key.maxlights, key.curlights), (7, 7));
+        //a second speed die before the reserved slot
+        assert_eq!(key.speed.len(), 3);
+        assert_eq!(key.reserved_slot(), 2);
+        assert_eq!(key.speed[1].range(), (1, 4));
+
+        for _ in 0..20 {
+            key.emotion.gain(CoinSource::ClashWin);
+        }
+        assert_eq!(key.level_up(), 1);
+        assert_eq!(key.emotion.level, MAX_LEVEL);
+        assert_eq!(key.emotion.threshold(), None);
+        assert_eq!(key.emotion.coins, 0);
+    }
+}
@@ -0,0 +1,65 @@
+// rule violations of LOR combat
+
+use crate::deck::DECK_SIZE;
+use crate::object::DiceType;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CombatError {
+    //a key page only takes speed dice
+    NotSpeedDice(DiceType),
+    //a combat page never takes speed dice
+    SpeedInCombatPage(usize),
+    //PageType::Key handed to a combat page
+    KeyPageType,
+    //a speed die handed to KeyPage::eval
+    SpeedResult,
+    //dice compared before being rolled
+    Unrolled,
+    //a speed die compared with a combat die
+    MixedClash,
+    //two counter dice never clash each other
+    CounterClash,
+    NonPositiveHealth(i32),
+    NonPositiveStagger(i32),
+    NegativeLights(i32),
+    NegativeCost(i32),
+    //(cost, light left)
+    NotEnoughLight(i32, i32),
+    //a deck holds exactly DECK_SIZE pages
+    DeckSize(usize),
+    //a unit with a deck only plays pages from its hand
+    PageNotInHand(String),
+}
+
+impl fmt::Display for CombatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotSpeedDice(dtype) => {
+                write!(f, "key page speed slot takes speed dice, got {:?}", dtype)
+            }
+            Self::SpeedInCombatPage(index) => {
+                write!(f, "combat page dice {} is a speed die", index)
+            }
+            Self::KeyPageType => write!(f, "combat page cannot have the key page type"),
+            Self::SpeedResult => write!(f, "key page cannot evaluate a speed result"),
+            Self::Unrolled => write!(f, "dice must be rolled before they clash"),
+            Self::MixedClash => write!(f, "speed dice only clash with speed dice"),
+            Self::CounterClash => write!(f, "counter dice cannot clash with counter dice"),
+            Self::NonPositiveHealth(x) => write!(f, "key page health must be positive, got {}", x),
+            Self::NonPositiveStagger(x) => {
+                write!(f, "key page stagger must be positive, got {}", x)
+            }
+            Self::NegativeLights(x) => write!(f, "key page lights cannot be negative, got {}", x),
+            Self::NegativeCost(x) => write!(f, "combat page cost cannot be negative, got {}", x),
+            Self::NotEnoughLight(cost, lights) => {
+                write!(f, "page costs {} light, only {} left", cost, lights)
+            }
+            Self::DeckSize(x) => write!(f, "deck must hold {} pages, got {}", DECK_SIZE, x),
+            Self::PageNotInHand(name) => write!(f, "{} is not in the hand", name),
+        }
+    }
+}
+
+impl Error for CombatError {}
@@ -0,0 +1,30 @@
+// test fixtures shared across the modules of LOR combat
+
+use crate::object::{
+    AttackDice, CombatDice, CombatPage, CombatPageBuilder, Dice, DiceType, KeyPage, KeyPageBuilder,
+    NormalDice,
+};
+
+pub fn slash_dice(min: i32, max: i32) -> Dice {
+    Dice::new(
+        DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Slash))),
+        min,
+        max,
+    )
+}
+
+//one slash die that always lands on `value`
+pub fn slash_page(value: i32) -> CombatPage {
+    CombatPageBuilder::new()
+        .dice(slash_dice(value, value))
+        .build()
+}
+
+pub fn key() -> KeyPage {
+    KeyPageBuilder::new()
+        .health(50)
+        .stagger(20)
+        .lights(3)
+        .speed(Dice::new(DiceType::Speed, 2, 2))
+        .build()
+}
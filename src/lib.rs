@@ -1 +1,17 @@
+pub mod abnormality;
+pub mod battle;
+pub mod clash;
+pub mod damage;
+pub mod deck;
+pub mod effect;
+pub mod emotion;
+pub mod error;
+#[cfg(test)]
+mod fixtures;
 pub mod object;
+pub mod power;
+pub mod probability;
+pub mod resource;
+pub mod rng;
+pub mod simulation;
+pub mod status;
@@ -1,5 +1,14 @@
 // basic object of LOR combat
 
+use crate::abnormality::AbnormalityPage;
+use crate::clash::{ClashKind, ClashRecord, ClashReport, Damage};
+use crate::damage::{DamageModifiers, DamagePipeline, HitBreakdown};
+use crate::deck::{Deck, Hand};
+use crate::effect::{self, Effect, EffectContext, Trigger};
+use crate::emotion::{self, Emotion};
+use crate::error::CombatError;
+use crate::power::{DicePower, PowerModifier, PowerSource};
+use crate::status::{self, StatusContainer};
 use rand::Rng;
 use std::ops::Sub;
 
@@ -11,32 +20,32 @@ use std::ops::Sub;
 //Defense Dice(block, evade)
 //Counter Dice
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum DiceType {
     Speed,
     Combat(CombatDice),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum CombatDice {
     Normal(NormalDice),
     Counter(NormalDice),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum NormalDice {
     Attack(AttackDice),
     Defense(DefenseDice),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum AttackDice {
     Slash,
     Pierce,
     Blunt,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum DefenseDice {
     Block,
     Evade,
@@ -47,7 +56,20 @@ pub struct Dice {
     min: i32,
     max: i32,
     cur: i32,
+    raw: i32,
+    power: DicePower,
+    //the share of PowerSource::Status that came from the owner's statuses
+    statuses: i32,
     dtype: DiceType,
+    effects: Vec<Effect>,
+}
+
+//side of a dice comparison, read from the sign of the result
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Winner {
+    Own,
+    Other,
+    Draw,
 }
 
 //Page(key, combat)
@@ -86,7 +108,7 @@ pub enum MassPageType {
     Individual,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PageRarity {
     Paperback,
     Hardcover,
@@ -136,6 +158,16 @@ impl KeyPageResistances {
             AttackDice::Blunt => (&self.hblunt, &self.sblunt),
         }
     }
+
+    pub fn set(&mut self, dtype: &AttackDice, health: Resistance, stagger: Resistance) {
+        let (h, s) = match dtype {
+            AttackDice::Slash => (&mut self.hslash, &mut self.sslash),
+            AttackDice::Pierce => (&mut self.hpierce, &mut self.spierce),
+            AttackDice::Blunt => (&mut self.hblunt, &mut self.sblunt),
+        };
+        *h = health;
+        *s = stagger;
+    }
 }
 
 #[derive(Clone)]
@@ -150,6 +182,34 @@ pub struct KeyPage {
     pub curlights: i32,
     pub curhealth: i32,
     pub curstagger: i32,
+    pub power: Vec<PowerModifier>,
+    //pages effects asked the owner to draw, settled by whoever holds the deck
+    pub draws: i32,
+    //counter and defense dice kept on the reserved speed slot for this scene
+    pub reserved: Vec<Dice>,
+    pub stagger_state: StaggerState,
+    pub pipeline: DamagePipeline,
+    //layers added to damage this key page deals, and to damage it takes
+    pub outgoing: DamageModifiers,
+    pub incoming: DamageModifiers,
+    pub statuses: StatusContainer,
+    pub hand: Hand,
+    pub emotion: Emotion,
+    //picked on emotion level ups, see AbnormalityPage::apply
+    pub abnormalities: Vec<AbnormalityPage>,
+}
+
+//scene ends a staggered key page sits through before it recovers: the rest
+//of the scene it broke in, then the whole following scene
+pub const STAGGER_SCENES: u32 = 2;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StaggerState {
+    #[default]
+    Normal,
+    Staggered {
+        scenes: u32,
+    },
 }
 
 #[derive(Clone)]
@@ -161,6 +221,9 @@ pub struct KeyPageBuilder {
     maxstagger: i32,
     lights: i32,
     resistances: KeyPageResistances,
+    power: Vec<PowerModifier>,
+    pipeline: DamagePipeline,
+    deck: Deck,
 }
 
 impl KeyPageBuilder {
@@ -180,6 +243,9 @@ impl KeyPageBuilder {
                 spierce: Resistance::Normal,
                 sblunt: Resistance::Normal,
             },
+            power: Vec::new(),
+            pipeline: DamagePipeline::default(),
+            deck: Deck::default(),
         }
     }
 
@@ -193,13 +259,17 @@ impl KeyPageBuilder {
         self
     }
 
-    pub fn speed(mut self, speed: Dice) -> Self {
+    pub fn speed(self, speed: Dice) -> Self {
+        self.try_speed(speed).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_speed(mut self, speed: Dice) -> Result<Self, CombatError> {
         match speed.dtype() {
             DiceType::Speed => {}
-            _ => unreachable!(),
+            dtype => return Err(CombatError::NotSpeedDice(dtype.clone())),
         };
         self.speed.push(speed);
-        self
+        Ok(self)
     }
 
     pub fn health(mut self, health: i32) -> Self {
@@ -252,6 +322,35 @@ impl KeyPageBuilder {
         self
     }
 
+    //passive power the key page carries into every scene
+    pub fn power(mut self, modifier: PowerModifier) -> Self {
+        self.power.push(modifier);
+        self
+    }
+
+    pub fn pipeline(mut self, pipeline: DamagePipeline) -> Self {
+        self.pipeline = pipeline;
+        self
+    }
+
+    pub fn deck(mut self, deck: Deck) -> Self {
+        self.deck = deck;
+        self
+    }
+
+    pub fn try_build(self) -> Result<KeyPage, CombatError> {
+        if self.maxhealth <= 0 {
+            return Err(CombatError::NonPositiveHealth(self.maxhealth));
+        }
+        if self.maxstagger <= 0 {
+            return Err(CombatError::NonPositiveStagger(self.maxstagger));
+        }
+        if self.lights < 0 {
+            return Err(CombatError::NegativeLights(self.lights));
+        }
+        Ok(self.build())
+    }
+
     pub fn build(mut self) -> KeyPage {
         //for preserved defense/counter dice
         self.speed.push(Dice::new(DiceType::Speed, 0, 0));
@@ -266,6 +365,17 @@ impl KeyPageBuilder {
             curlights: self.lights,
             curhealth: self.maxhealth,
             curstagger: self.maxstagger,
+            power: self.power,
+            draws: 0,
+            reserved: Vec::new(),
+            stagger_state: StaggerState::Normal,
+            pipeline: self.pipeline,
+            outgoing: DamageModifiers::default(),
+            incoming: DamageModifiers::default(),
+            statuses: StatusContainer::default(),
+            hand: Hand::new(&self.deck),
+            emotion: Emotion::default(),
+            abnormalities: Vec::new(),
         }
     }
 }
@@ -278,37 +388,96 @@ impl Default for KeyPageBuilder {
 
 impl KeyPage {
     pub fn eval(&mut self, result: &Dice) {
-        match result.dtype() {
-            DiceType::Speed => unreachable!(),
-            DiceType::Combat(CombatDice::Normal(dice))
-            | DiceType::Combat(CombatDice::Counter(dice)) => {
-                match dice {
-                    NormalDice::Attack(atype) => {
-                        if result.cur() < 0 {
-                            let (hresist, sresist) = self.resistances.get(atype);
-                            self.curhealth += (hresist.number() * result.cur() as f32) as i32;
-                            self.curstagger += (sresist.number() * result.cur() as f32) as i32;
-                        }
-                    }
-                    NormalDice::Defense(DefenseDice::Block) => {
-                        if result.cur() < 0 {
-                            self.curstagger += result.cur();
-                            if self.curstagger > self.maxstagger {
-                                self.curstagger = self.maxstagger;
-                            }
-                        }
-                    }
-                    NormalDice::Defense(DefenseDice::Evade) => {
-                        if result.cur() > 0 {
-                            self.curstagger += result.cur();
-                            if self.curstagger > self.maxstagger {
-                                self.curstagger = self.maxstagger;
-                            }
-                        }
-                    }
-                };
+        self.try_eval(result).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    //result is read from this key page's side, a negative attack hurts it
+    pub fn try_eval(&mut self, result: &Dice) -> Result<(), CombatError> {
+        if let DiceType::Speed = result.dtype() {
+            return Err(CombatError::SpeedResult);
+        }
+        let record = ClashRecord::new(None, None, result.clone());
+        self.take(&record.own_damage);
+        Ok(())
+    }
+
+    //apply one side of a clash record, attack damage goes through resistances
+    pub fn take(&mut self, damage: &Damage) -> HitBreakdown {
+        self.take_from(damage, &DamageModifiers::default())
+    }
+
+    //same as take, with the attacker's outgoing layers added to this key
+    //page's incoming ones and its statuses' before the pipeline runs
+    pub fn take_from(&mut self, damage: &Damage, attacker: &DamageModifiers) -> HitBreakdown {
+        let modifiers = *attacker + self.incoming + self.statuses.incoming();
+        let (hresist, sresist) = match &damage.attack {
+            Some(atype) => {
+                let (hresist, sresist) = self.resistance(atype);
+                (hresist.number(), sresist.number())
             }
+            None => (1.0, 1.0),
         };
+        let mut breakdown = HitBreakdown::default();
+        if damage.health > 0 {
+            breakdown.health = self.pipeline.run(damage.health, &modifiers.health, hresist);
+        }
+        if damage.stagger > 0 {
+            breakdown.stagger = self
+                .pipeline
+                .run(damage.stagger, &modifiers.stagger, sresist);
+        }
+
+        self.curhealth -= breakdown.health.value;
+        self.curstagger -= breakdown.stagger.value;
+        self.curstagger += damage.recovery;
+        if self.curstagger > self.maxstagger {
+            self.curstagger = self.maxstagger;
+        }
+        self.update_stagger();
+        breakdown
+    }
+
+    //(health, stagger) resistance actually used, a staggered key page
+    //takes fatal damage whatever its resistances say
+    pub fn resistance(&self, atype: &AttackDice) -> (&Resistance, &Resistance) {
+        if self.is_staggered() {
+            return (&Resistance::Fatal, &Resistance::Fatal);
+        }
+        self.resistances.get(atype)
+    }
+
+    //break once stagger runs out; a staggered key page stays at 0 and
+    //loses the dice it kept on the reserved slot
+    pub fn update_stagger(&mut self) {
+        if self.is_staggered() {
+            self.curstagger = 0;
+            return;
+        }
+        if self.curstagger <= 0 {
+            self.curstagger = 0;
+            self.stagger_state = StaggerState::Staggered {
+                scenes: STAGGER_SCENES,
+            };
+            self.reserved.clear();
+        }
+    }
+
+    pub fn is_staggered(&self) -> bool {
+        matches!(self.stagger_state, StaggerState::Staggered { .. })
+    }
+
+    pub fn add_power(&mut self, modifier: PowerModifier) {
+        self.power.push(modifier);
+    }
+
+    //drop every modifier of one source, e.g. page power at scene end
+    pub fn clear_power(&mut self, source: PowerSource) {
+        self.power.retain(|x| x.source != source);
+    }
+
+    //apply the key page's modifiers to a page it is about to use
+    pub fn empower(&self, page: &mut CombatPage) {
+        self.power.iter().for_each(|x| x.apply_page(page));
     }
 
     pub fn health(&self) -> i32 {
@@ -319,8 +488,145 @@ impl KeyPage {
         self.curstagger
     }
 
+    //draw the pages effects queued up
+    pub fn settle_draws_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        if self.draws > 0 {
+            self.hand.draw_with(self.draws as usize, rng);
+        }
+        self.draws = 0;
+    }
+
+    pub fn can_afford(&self, page: &CombatPage) -> bool {
+        page.cost <= self.curlights
+    }
+
+    //pay for a page, nothing is spent when the light is not there
+    pub fn spend_light(&mut self, page: &CombatPage) -> Result<(), CombatError> {
+        if !self.can_afford(page) {
+            return Err(CombatError::NotEnoughLight(page.cost, self.curlights));
+        }
+        self.curlights -= page.cost;
+        Ok(())
+    }
+
+    //put a page on a speed die: it comes from the hand, unless the unit has
+    //no deck and plays whatever it is handed, and is paid for; nothing
+    //changes when either check fails
+    pub fn play(&mut self, page: &CombatPage) -> Result<(), CombatError> {
+        if !self.hand.is_deckless() && !self.hand.contains(page.name()) {
+            return Err(CombatError::PageNotInHand(page.name().to_string()));
+        }
+        self.spend_light(page)?;
+        self.hand.play_named(page.name());
+        Ok(())
+    }
+
+    //never above maxlights
+    pub fn restore_light(&mut self, amount: i32) {
+        self.curlights = (self.curlights + amount).min(self.maxlights);
+    }
+
+    //index of the speed die pushed by the builder for preserved dice
+    pub fn reserved_slot(&self) -> usize {
+        self.speed.len() - 1
+    }
+
+    //keep unused counter and defense dice, offensive dice are never stored;
+    //a staggered unit keeps nothing
+    pub fn reserve(&mut self, dices: Vec<Dice>) {
+        if self.is_staggered() {
+            return;
+        }
+        self.reserved
+            .extend(dices.into_iter().filter(|x| !x.is_unopposed()));
+    }
+
+    pub fn take_reserved(&mut self) -> Vec<Dice> {
+        std::mem::take(&mut self.reserved)
+    }
+
+    //"next scene" statuses and those abnormality pages grant become active
+    //before their scene start hooks run. abnormality statuses are granted
+    //again every scene on purpose: most statuses clear at scene end, while
+    //the page lasts the whole reception
+    pub fn start_scene(&mut self) {
+        let granted: Vec<_> = self
+            .abnormalities
+            .iter()
+            .flat_map(|x| x.statuses.iter().cloned())
+            .collect();
+        for (effect, stacks) in granted {
+            self.statuses.add_next(effect, stacks);
+        }
+        status::scene_start(self);
+    }
+
+    //start_scene followed by the speed roll, so next scene haste counts
+    pub fn start_scene_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.start_scene();
+        self.roll_speed_with(rng);
+    }
+
+    pub fn roll_speed(&mut self) {
+        self.roll_speed_with(&mut rand::thread_rng())
+    }
+
+    //roll every speed die but the reserved slot, haste and bind shift the
+    //values as status power
+    pub fn roll_speed_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let power = self.statuses.power(&DiceType::Speed);
+        let slot = self.reserved_slot();
+        for dice in self.speed[..slot].iter_mut() {
+            dice.roll_with(rng);
+            dice.set_status_power(power);
+        }
+    }
+
+    //usable speed dice, fastest first; ties keep slot order
+    pub fn speed_order(&self) -> Vec<usize> {
+        let speed = match self.gets() {
+            None => return Vec::new(),
+            Some(speed) => speed,
+        };
+        let mut order: Vec<usize> = (0..self.reserved_slot()).collect();
+        order.sort_by_key(|&x| -speed[x].cur());
+        order
+    }
+
+    //strictly faster than the other die, what a redirect needs; a die
+    //that cannot act is never faster and is always outsped
+    pub fn outspeeds(&self, index: usize, other: &KeyPage, other_index: usize) -> bool {
+        match (self.get(index), other.get(other_index)) {
+            (Some(own), Some(other)) => own.cur() > other.cur(),
+            (Some(_), None) => true,
+            _ => false,
+        }
+    }
+
+    //turn the emotion coins gained so far into levels, returns levels gained
+    pub fn level_up(&mut self) -> u32 {
+        emotion::level_up(self)
+    }
+
+    pub fn end_scene(&mut self) {
+        status::scene_end(self);
+        self.reserved.clear();
+        if let StaggerState::Staggered { scenes } = self.stagger_state {
+            match scenes {
+                0 | 1 => {
+                    self.stagger_state = StaggerState::Normal;
+                    self.curstagger = self.maxstagger;
+                }
+                scenes => {
+                    self.stagger_state = StaggerState::Staggered { scenes: scenes - 1 };
+                }
+            }
+        }
+    }
+
+    //staggered or dead key pages have no speed dice to act with
     pub fn gets(&self) -> Option<&Vec<Dice>> {
-        if self.curstagger <= 0 || self.curhealth <= 0 {
+        if self.is_staggered() || self.curstagger <= 0 || self.curhealth <= 0 {
             return None;
         }
         Some(&self.speed)
@@ -335,6 +641,27 @@ impl KeyPage {
     }
 }
 
+//dice effects fire before the effects of the page holding them
+fn trigger(
+    trigger: Trigger,
+    dice: Option<&Dice>,
+    page: &[Effect],
+    owner: &mut KeyPage,
+    target: &mut KeyPage,
+    opponent: Option<&Dice>,
+) {
+    let mut ctx = EffectContext {
+        owner,
+        target,
+        dice,
+        opponent,
+    };
+    if let Some(dice) = dice {
+        effect::fire(dice.effects(), trigger, &mut ctx);
+    }
+    effect::fire(page, trigger, &mut ctx);
+}
+
 impl Page for KeyPage {
     fn ptype(&self) -> PageType {
         PageType::Key
@@ -353,8 +680,25 @@ pub struct CombatPage {
     pub rarity: PageRarity,
     pub dices: Vec<Dice>,
     pub ptype: PageType,
+    pub effects: Vec<Effect>,
+    //light spent to put the page on a speed die
+    pub cost: i32,
+    //name of the only key page whose deck may hold this page
+    pub exclusive: Option<String>,
 }
 
+//what CombatPage::duel reports while it runs
+enum Duel<'a> {
+    //a die was just rolled and not compared yet, true for the own side
+    Rolled(bool, &'a mut Dice),
+    //whether a side may still use its reserved dice, true for the own side
+    Reserve(bool, &'a mut bool),
+    Record(&'a mut ClashRecord),
+}
+
+//(result, self, other)
+pub type Evaluation = (Vec<Dice>, Vec<Dice>, Vec<Dice>);
+
 impl Default for CombatPage {
     fn default() -> Self {
         CombatPageBuilder::default().build()
@@ -370,6 +714,10 @@ impl CombatPage {
         &self.rarity
     }
 
+    pub fn cost(&self) -> i32 {
+        self.cost
+    }
+
     pub fn gets(&self) -> &Vec<Dice> {
         &self.dices
     }
@@ -378,49 +726,259 @@ impl CombatPage {
         self.dices.get(index)
     }
 
-    //(result, self, other)
-    pub fn eval(self, other: Self) -> (Vec<Dice>, Vec<Dice>, Vec<Dice>) {
-        let self_dices = self.dices;
-        let other_dices = other.dices;
-        let mut results = Vec::new();
-        let mut self_recycle = Vec::new();
-        let mut other_recycle = Vec::new();
+    //(result, self, other): clash results followed by one-sided hits, then
+    //the leftover defensive and counter dice of each side
+    pub fn eval(self, other: Self) -> Evaluation {
+        self.eval_with(other, &mut rand::thread_rng())
+    }
+
+    pub fn eval_with<R: Rng + ?Sized>(self, other: Self, rng: &mut R) -> Evaluation {
+        self.resolve_with(other, rng).into_evaluation()
+    }
+
+    //eval with one record per dice pair instead of signed result dice
+    pub fn resolve(self, other: Self) -> ClashReport {
+        self.resolve_with(other, &mut rand::thread_rng())
+    }
+
+    pub fn resolve_with<R: Rng + ?Sized>(self, other: Self, rng: &mut R) -> ClashReport {
+        self.duel(other, Vec::new(), Vec::new(), rng, |_| {})
+    }
+
+    //every dice pair that will clash must be able to
+    pub fn can_clash(&self, other: &Self) -> Result<(), CombatError> {
+        self.dices
+            .iter()
+            .zip(other.dices.iter())
+            .try_for_each(|(x, y)| x.can_clash(y))
+    }
+
+    pub fn try_eval_with<R: Rng + ?Sized>(
+        self,
+        other: Self,
+        rng: &mut R,
+    ) -> Result<Evaluation, CombatError> {
+        self.can_clash(&other)?;
+        Ok(self.eval_with(other, rng))
+    }
+
+    pub fn try_resolve_with<R: Rng + ?Sized>(
+        self,
+        other: Self,
+        rng: &mut R,
+    ) -> Result<ClashReport, CombatError> {
+        self.can_clash(&other)?;
+        Ok(self.resolve_with(other, rng))
+    }
+
+    pub fn try_clash<R: Rng + ?Sized>(
+        self,
+        other: Self,
+        own: &mut KeyPage,
+        target: &mut KeyPage,
+        rng: &mut R,
+    ) -> Result<ClashReport, CombatError> {
+        self.can_clash(&other)?;
+        Ok(self.clash(other, own, target, rng))
+    }
+
+    //resolve against both key pages: each record is applied to the side it
+    //hurts, then page and dice effects fire; offensive dice left once a page
+    //runs out face the dice the other side kept on its reserved slot, and
+    //leftover counter and defense dice go back to each side's reserved slot
+    pub fn clash<R: Rng + ?Sized>(
+        self,
+        other: Self,
+        own: &mut KeyPage,
+        target: &mut KeyPage,
+        rng: &mut R,
+    ) -> ClashReport {
+        let own_page = self.effects.clone();
+        let other_page = other.effects.clone();
+        trigger(Trigger::OnUse, None, &own_page, own, target, None);
+        trigger(Trigger::OnUse, None, &other_page, target, own, None);
+
+        let (own_reserved, other_reserved) = (own.take_reserved(), target.take_reserved());
+        let report = self.duel(other, own_reserved, other_reserved, rng, |event| {
+            let record = match event {
+                Duel::Rolled(true, dice) => return status::dice_roll(own, dice),
+                Duel::Rolled(false, dice) => return status::dice_roll(target, dice),
+                //a unit staggered mid clash loses its reserved dice
+                Duel::Reserve(true, usable) => return *usable = !own.is_staggered(),
+                Duel::Reserve(false, usable) => return *usable = !target.is_staggered(),
+                Duel::Record(record) => record,
+            };
+            let (own_dice, other_dice) = (record.own.as_ref(), record.other.as_ref());
+            if let Some(dice) = own_dice {
+                trigger(Trigger::OnUse, Some(dice), &[], own, target, other_dice);
+            }
+            if let Some(dice) = other_dice {
+                trigger(Trigger::OnUse, Some(dice), &[], target, own, own_dice);
+            }
+            let own_out = own.outgoing + own.statuses.outgoing();
+            let target_out = target.outgoing + target.statuses.outgoing();
+            record.own_breakdown = own.take_from(&record.own_damage, &target_out);
+            record.other_breakdown = target.take_from(&record.other_damage, &own_out);
+            emotion::record(own, target, record);
+
+            let (winner_page, winner_key, loser_page, loser_key) = match record.winner {
+                Winner::Own => (&own_page, &mut *own, &other_page, &mut *target),
+                Winner::Other => (&other_page, &mut *target, &own_page, &mut *own),
+                Winner::Draw => return,
+            };
+            let (winner, loser) = (record.winning_dice(), record.losing_dice());
+            //one-sided hits have no clash to win or lose
+            if !record.is_unopposed() {
+                trigger(
+                    Trigger::ClashWin,
+                    winner,
+                    winner_page,
+                    winner_key,
+                    loser_key,
+                    loser,
+                );
+                trigger(
+                    Trigger::ClashLose,
+                    loser,
+                    loser_page,
+                    loser_key,
+                    winner_key,
+                    winner,
+                );
+            }
+            if record.kind == ClashKind::Hit {
+                trigger(
+                    Trigger::OnHit,
+                    winner,
+                    winner_page,
+                    winner_key,
+                    loser_key,
+                    loser,
+                );
+                status::hit(winner_key, loser_key);
+            }
+        });
+
+        own.reserve(report.own_unused.clone());
+        target.reserve(report.other_unused.clone());
+        report
+    }
 
-        let mut self_dices_iter = self_dices.into_iter();
-        let mut other_dices_iter = other_dices.into_iter();
+    //pairs dice in order, `each` sees every die once rolled and every record
+    //as it is made; once one side runs out, its opponent's offensive dice
+    //clash its reserved dice and then hit one-sided, the rest are returned
+    //unused along with the reserved dice nothing met
+    fn duel<R, F>(
+        self,
+        other: Self,
+        self_reserved: Vec<Dice>,
+        other_reserved: Vec<Dice>,
+        rng: &mut R,
+        mut each: F,
+    ) -> ClashReport
+    where
+        R: Rng + ?Sized,
+        F: FnMut(Duel),
+    {
+        let mut report = ClashReport::default();
+        let mut self_dices_iter = self.dices.into_iter();
+        let mut other_dices_iter = other.dices.into_iter();
+        let mut self_reserved = self_reserved.into_iter();
+        let mut other_reserved = other_reserved.into_iter();
         loop {
             let self_next = self_dices_iter.next();
             let other_next = other_dices_iter.next();
-            match (self_next, other_next) {
+            let record = match (self_next, other_next) {
                 (None, None) => break,
                 (None, Some(mut other_next)) => {
-                    other_next.roll();
-                    other_recycle.push(other_next);
-                    break;
+                    other_next.roll_with(rng);
+                    each(Duel::Rolled(false, &mut other_next));
+                    if !other_next.is_unopposed() {
+                        report.other_unused.push(other_next);
+                        continue;
+                    }
+                    let mut usable = true;
+                    each(Duel::Reserve(true, &mut usable));
+                    match self_reserved.next().filter(|_| usable) {
+                        Some(mut self_dice) => {
+                            self_dice.roll_with(rng);
+                            each(Duel::Rolled(true, &mut self_dice));
+                            let result = self_dice.clone() - other_next.clone();
+                            ClashRecord::new(Some(self_dice), Some(other_next), result)
+                        }
+                        None => {
+                            let result = Dice::result(other_next.dtype.clone(), -other_next.cur);
+                            ClashRecord::new(None, Some(other_next), result)
+                        }
+                    }
                 }
                 (Some(mut self_next), None) => {
-                    self_next.roll();
-                    self_recycle.push(self_next);
-                    break;
+                    self_next.roll_with(rng);
+                    each(Duel::Rolled(true, &mut self_next));
+                    if !self_next.is_unopposed() {
+                        report.own_unused.push(self_next);
+                        continue;
+                    }
+                    let mut usable = true;
+                    each(Duel::Reserve(false, &mut usable));
+                    match other_reserved.next().filter(|_| usable) {
+                        Some(mut other_dice) => {
+                            other_dice.roll_with(rng);
+                            each(Duel::Rolled(false, &mut other_dice));
+                            let result = self_next.clone() - other_dice.clone();
+                            ClashRecord::new(Some(self_next), Some(other_dice), result)
+                        }
+                        None => {
+                            let result = Dice::result(self_next.dtype.clone(), self_next.cur);
+                            ClashRecord::new(Some(self_next), None, result)
+                        }
+                    }
                 }
                 (Some(mut self_dice), Some(mut other_dice)) => {
-                    self_dice.roll();
-                    other_dice.roll();
-                    results.push(self_dice - other_dice);
+                    self_dice.roll_with(rng);
+                    other_dice.roll_with(rng);
+                    each(Duel::Rolled(true, &mut self_dice));
+                    each(Duel::Rolled(false, &mut other_dice));
+                    let result = self_dice.clone() - other_dice.clone();
+                    ClashRecord::new(Some(self_dice), Some(other_dice), result)
                 }
             };
+            let mut record = record;
+            each(Duel::Record(&mut record));
+            report.records.push(record);
         }
+        report.own_unused.extend(self_reserved);
+        report.other_unused.extend(other_reserved);
 
-        self_dices_iter.for_each(|mut x| {
-            x.roll();
-            self_recycle.push(x);
-        });
-        other_dices_iter.for_each(|mut x| {
-            x.roll();
-            other_recycle.push(x);
-        });
+        report
+    }
+
+    //page used without a clash: offensive dice hit the target for their full
+    //value unless it has reserved dice, defensive and counter dice are
+    //returned unused and kept on the user's reserved slot. only offensive
+    //dice meet reserved dice, so counter dice never clash each other here
+    pub fn one_sided<R: Rng + ?Sized>(
+        self,
+        own: &mut KeyPage,
+        target: &mut KeyPage,
+        rng: &mut R,
+    ) -> ClashReport {
+        self.clash(Self::default(), own, target, rng)
+    }
 
-        (results, self_recycle, other_recycle)
+    //offensive dice meet the target's reserved dice in order
+    pub fn try_one_sided<R: Rng + ?Sized>(
+        self,
+        own: &mut KeyPage,
+        target: &mut KeyPage,
+        rng: &mut R,
+    ) -> Result<ClashReport, CombatError> {
+        self.dices
+            .iter()
+            .filter(|x| x.is_unopposed())
+            .zip(target.reserved.iter())
+            .try_for_each(|(x, y)| x.can_clash(y))?;
+        Ok(self.one_sided(own, target, rng))
     }
 }
 
@@ -430,6 +988,9 @@ pub struct CombatPageBuilder {
     rarity: PageRarity,
     dices: Vec<Dice>,
     ptype: PageType,
+    effects: Vec<Effect>,
+    cost: i32,
+    exclusive: Option<String>,
 }
 
 impl CombatPageBuilder {
@@ -439,6 +1000,9 @@ impl CombatPageBuilder {
             rarity: PageRarity::Paperback,
             dices: Vec::new(),
             ptype: PageType::Combat(CombatPageType::Melee(MeleePageType::NormalMelee)),
+            effects: Vec::new(),
+            cost: 0,
+            exclusive: None,
         }
     }
 
@@ -452,25 +1016,61 @@ impl CombatPageBuilder {
         self
     }
 
+    pub fn cost(mut self, cost: i32) -> Self {
+        self.cost = cost;
+        self
+    }
+
+    pub fn exclusive(mut self, key: &str) -> Self {
+        self.exclusive = Some(key.to_string());
+        self
+    }
+
     pub fn dice(mut self, dice: Dice) -> Self {
         self.dices.push(dice);
         self
     }
 
-    pub fn ptype(mut self, ptype: PageType) -> Self {
+    pub fn ptype(self, ptype: PageType) -> Self {
+        self.try_ptype(ptype).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_ptype(mut self, ptype: PageType) -> Result<Self, CombatError> {
         match ptype {
-            PageType::Key => unreachable!(),
+            PageType::Key => return Err(CombatError::KeyPageType),
             _ => self.ptype = ptype,
         };
+        Ok(self)
+    }
+
+    pub fn effect(mut self, effect: Effect) -> Self {
+        self.effects.push(effect);
         self
     }
 
+    pub fn try_build(self) -> Result<CombatPage, CombatError> {
+        if self.cost < 0 {
+            return Err(CombatError::NegativeCost(self.cost));
+        }
+        match self
+            .dices
+            .iter()
+            .position(|x| matches!(x.dtype(), DiceType::Speed))
+        {
+            Some(index) => Err(CombatError::SpeedInCombatPage(index)),
+            None => Ok(self.build()),
+        }
+    }
+
     pub fn build(self) -> CombatPage {
         CombatPage {
             name: self.name,
             rarity: self.rarity,
             dices: self.dices,
             ptype: self.ptype,
+            effects: self.effects,
+            cost: self.cost,
+            exclusive: self.exclusive,
         }
     }
 }
@@ -487,16 +1087,37 @@ impl Dice {
             min,
             max,
             cur: -1,
+            raw: -1,
+            power: DicePower::default(),
+            statuses: 0,
+            dtype,
+            effects: Vec::new(),
+        }
+    }
+
+    fn result(dtype: DiceType, cur: i32) -> Self {
+        Self {
+            min: 0,
+            max: 0,
+            cur,
+            raw: cur,
+            power: DicePower::default(),
+            statuses: 0,
             dtype,
+            effects: Vec::new(),
         }
     }
 
     pub fn roll(&mut self) -> i32 {
+        self.roll_with(&mut rand::thread_rng())
+    }
+
+    pub fn roll_with<R: Rng + ?Sized>(&mut self, rng: &mut R) -> i32 {
         if self.min > self.max {
-            self.cur = self.max;
+            self.fix(self.max);
             return self.cur;
         }
-        self.cur = rand::thread_rng().gen_range(self.min..=self.max);
+        self.fix(rng.gen_range(self.min..=self.max));
         self.cur
     }
 
@@ -504,15 +1125,107 @@ impl Dice {
         self.cur
     }
 
+    //face the die landed on, before power
+    pub fn raw(&self) -> i32 {
+        self.raw
+    }
+
+    pub fn power(&self) -> &DicePower {
+        &self.power
+    }
+
+    pub fn power_mut(&mut self) -> &mut DicePower {
+        &mut self.power
+    }
+
+    pub fn add_power(&mut self, source: PowerSource, amount: i32) {
+        self.power.add(source, amount);
+    }
+
+    //swap the status power of the last roll for `power`, so reused dice do
+    //not stack it and other status sources on the die stay
+    pub(crate) fn set_status_power(&mut self, power: i32) {
+        self.power.add(PowerSource::Status, power - self.statuses);
+        self.statuses = power;
+        self.fix(self.raw);
+    }
+
+    pub fn with_effect(mut self, effect: Effect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+
+    pub fn effects(&self) -> &[Effect] {
+        &self.effects
+    }
+
+    pub fn is_counter(&self) -> bool {
+        matches!(self.dtype, DiceType::Combat(CombatDice::Counter(_)))
+    }
+
+    //dice that still hit once the opposing page has no dice left
+    pub fn is_unopposed(&self) -> bool {
+        self.is_offensive() && !self.is_counter()
+    }
+
+    //attack dice, counter or not
+    pub fn is_offensive(&self) -> bool {
+        matches!(
+            self.dtype,
+            DiceType::Combat(CombatDice::Normal(NormalDice::Attack(_)))
+                | DiceType::Combat(CombatDice::Counter(NormalDice::Attack(_)))
+        )
+    }
+
     pub fn dtype(&self) -> &DiceType {
         &self.dtype
     }
+
+    //(lowest, highest) value a roll can land on
+    pub fn range(&self) -> (i32, i32) {
+        if self.min > self.max {
+            return (self.max, self.max);
+        }
+        (self.min, self.max)
+    }
+
+    //only meaningful on a result die: positive means the left operand won
+    pub fn winner(&self) -> Winner {
+        match self.cur {
+            cur if cur > 0 => Winner::Own,
+            cur if cur < 0 => Winner::Other,
+            _ => Winner::Draw,
+        }
+    }
+
+    //the same result die as seen from the right operand
+    pub fn reversed(&self) -> Dice {
+        Self::result(self.dtype.clone(), -self.cur)
+    }
+
+    //land on a face, power is applied on top
+    pub fn fix(&mut self, value: i32) {
+        self.raw = value;
+        self.cur = self.power.apply(value);
+    }
 }
 
 impl NormalDice {
+    pub fn try_combat(
+        &self,
+        cur: i32,
+        other: &Self,
+        cur2: i32,
+    ) -> Result<(Self, i32), CombatError> {
+        if cur == -1 || cur2 == -1 {
+            return Err(CombatError::Unrolled);
+        }
+        Ok(self.combat(cur)(other, cur2))
+    }
+
     pub fn combat(&self, cur: i32) -> impl Fn(&Self, i32) -> (Self, i32) + '_ {
         assert!(cur != -1);
-        return move |d2: &Self, cur2: i32| -> (Self, i32) {
+        move |d2: &Self, cur2: i32| -> (Self, i32) {
             assert!(cur2 != -1);
             let other = d2.to_owned();
             let our = self.to_owned();
@@ -522,7 +1235,7 @@ impl NormalDice {
                         if cur > cur2 {
                             (Self::Attack(dice), cur)
                         } else if cur < cur2 {
-                            (Self::Attack(dice2), (-1) * cur2)
+                            (Self::Attack(dice2), -cur2)
                         } else {
                             (Self::Attack(dice), 0)
                         }
@@ -538,7 +1251,7 @@ impl NormalDice {
                         if cur > cur2 {
                             (Self::Attack(dice), cur)
                         } else if cur < cur2 {
-                            (Self::Defense(DefenseDice::Evade), (-1) * cur2)
+                            (Self::Defense(DefenseDice::Evade), -cur2)
                         } else {
                             (Self::Defense(DefenseDice::Evade), 0)
                         }
@@ -557,7 +1270,7 @@ impl NormalDice {
                         if cur > cur2 {
                             val = cur;
                         } else if cur < cur2 {
-                            val = (-1) * cur2;
+                            val = -cur2;
                         } else {
                             val = 0;
                         }
@@ -567,7 +1280,7 @@ impl NormalDice {
                         if cur > cur2 {
                             (Self::Defense(DefenseDice::Block), cur)
                         } else if cur < cur2 {
-                            (Self::Defense(DefenseDice::Evade), (-1) * cur2)
+                            (Self::Defense(DefenseDice::Evade), -cur2)
                         } else {
                             (Self::Defense(DefenseDice::Evade), 0)
                         }
@@ -578,7 +1291,7 @@ impl NormalDice {
                         if cur > cur2 {
                             (Self::Defense(DefenseDice::Evade), cur)
                         } else if cur < cur2 {
-                            (Self::Attack(dice2), (-1) * cur2)
+                            (Self::Attack(dice2), -cur2)
                         } else {
                             (Self::Defense(DefenseDice::Evade), 0)
                         }
@@ -587,7 +1300,7 @@ impl NormalDice {
                         if cur > cur2 {
                             (Self::Defense(DefenseDice::Evade), cur)
                         } else if cur < cur2 {
-                            (Self::Defense(DefenseDice::Block), (-1) * cur2)
+                            (Self::Defense(DefenseDice::Block), -cur2)
                         } else {
                             (Self::Defense(DefenseDice::Block), 0)
                         }
@@ -595,88 +1308,85 @@ impl NormalDice {
                     Self::Defense(DefenseDice::Evade) => (Self::Defense(DefenseDice::Evade), 0),
                 },
             }
-        };
+        }
     }
 }
 
-//dice combat
-impl Sub for Dice {
-    type Output = Dice;
+impl Dice {
+    //rules two dice must satisfy to clash, checked before anything is rolled
+    pub fn can_clash(&self, other: &Self) -> Result<(), CombatError> {
+        match (&self.dtype, &other.dtype) {
+            (DiceType::Speed, DiceType::Speed) => Ok(()),
+            (DiceType::Speed, _) | (_, DiceType::Speed) => Err(CombatError::MixedClash),
+            (
+                DiceType::Combat(CombatDice::Counter(_)),
+                DiceType::Combat(CombatDice::Counter(_)),
+            ) => Err(CombatError::CounterClash),
+            _ => Ok(()),
+        }
+    }
 
-    fn sub(self, other: Self) -> Self::Output {
-        assert!(self.cur != -1);
-        assert!(other.cur != -1);
+    pub fn try_clash(self, other: Self) -> Result<Dice, CombatError> {
+        self.can_clash(&other)?;
+        if self.cur == -1 || other.cur == -1 {
+            return Err(CombatError::Unrolled);
+        }
 
-        match self.dtype {
+        Ok(match (self.dtype, other.dtype) {
             //speed comparasion
-            DiceType::Speed => match other.dtype {
-                DiceType::Speed => Self {
-                    min: 0,
-                    max: 0,
-                    cur: self.cur - other.cur,
-                    dtype: DiceType::Speed,
-                },
-                _ => unreachable!(),
-            },
+            (DiceType::Speed, _) => Self::result(DiceType::Speed, self.cur - other.cur),
 
             //combat
-            DiceType::Combat(CombatDice::Normal(dice)) => match other.dtype {
-                DiceType::Speed => unreachable!(),
-                DiceType::Combat(CombatDice::Normal(dice2)) => {
-                    let (side, val) = dice.combat(self.cur)(&dice2, other.cur);
-                    Self {
-                        min: 0,
-                        max: 0,
-                        cur: val,
-                        dtype: DiceType::Combat(CombatDice::Normal(side)),
+            (
+                DiceType::Combat(CombatDice::Normal(dice)),
+                DiceType::Combat(CombatDice::Normal(dice2)),
+            ) => {
+                let (side, val) = dice.try_combat(self.cur, &dice2, other.cur)?;
+                Self::result(DiceType::Combat(CombatDice::Normal(side)), val)
+            }
+            (
+                DiceType::Combat(CombatDice::Normal(dice)),
+                DiceType::Combat(CombatDice::Counter(dice2)),
+            ) => {
+                let (side, val) = dice.try_combat(self.cur, &dice2, other.cur)?;
+                match val {
+                    val if val >= 0 => {
+                        Self::result(DiceType::Combat(CombatDice::Normal(side)), val)
                     }
+                    val => Self::result(DiceType::Combat(CombatDice::Counter(side)), val),
                 }
-                DiceType::Combat(CombatDice::Counter(dice2)) => {
-                    let (side, val) = dice.combat(self.cur)(&dice2, other.cur);
-                    match val {
-                        val if val >= 0 => Self {
-                            min: 0,
-                            max: 0,
-                            cur: val,
-                            dtype: DiceType::Combat(CombatDice::Normal(side)),
-                        },
-                        val => Self {
-                            min: 0,
-                            max: 0,
-                            cur: val,
-                            dtype: DiceType::Combat(CombatDice::Counter(side)),
-                        },
+            }
+            (
+                DiceType::Combat(CombatDice::Counter(dice)),
+                DiceType::Combat(CombatDice::Normal(dice2)),
+            ) => {
+                let (side, val) = dice.try_combat(self.cur, &dice2, other.cur)?;
+                match val {
+                    val if val >= 0 => {
+                        Self::result(DiceType::Combat(CombatDice::Counter(side)), val)
                     }
+                    val => Self::result(DiceType::Combat(CombatDice::Normal(side)), val),
                 }
-            },
+            }
+            _ => unreachable!("rejected by can_clash"),
+        })
+    }
+}
 
-            DiceType::Combat(CombatDice::Counter(dice)) => match other.dtype {
-                DiceType::Speed | DiceType::Combat(CombatDice::Counter(_)) => unreachable!(),
-                DiceType::Combat(CombatDice::Normal(dice2)) => {
-                    let (side, val) = dice.combat(self.cur)(&dice2, other.cur);
-                    match val {
-                        val if val >= 0 => Self {
-                            min: 0,
-                            max: 0,
-                            cur: val,
-                            dtype: DiceType::Combat(CombatDice::Counter(side)),
-                        },
-                        val => Self {
-                            min: 0,
-                            max: 0,
-                            cur: val,
-                            dtype: DiceType::Combat(CombatDice::Normal(side)),
-                        },
-                    }
-                }
-            },
-        }
+//dice combat
+impl Sub for Dice {
+    type Output = Dice;
+
+    fn sub(self, other: Self) -> Self::Output {
+        self.try_clash(other).unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::effect::RecoverStagger;
+    use std::sync::Arc;
 
     #[test]
     fn dice_roll() {
@@ -699,6 +1409,24 @@ mod tests {
         assert!(dice.cur() == 3);
     }
 
+    #[test]
+    fn dice_roll_seeded() {
+        let mut dice = Dice::new(DiceType::Speed, 1, 20);
+        let mut rng1 = crate::rng::seeded(42);
+        let mut rng2 = crate::rng::seeded(42);
+
+        let first: Vec<i32> = (0..16).map(|_| dice.roll_with(&mut rng1)).collect();
+        let second: Vec<i32> = (0..16).map(|_| dice.roll_with(&mut rng2)).collect();
+        assert_eq!(first, second);
+        //pinned, a seed from a bug report must keep replaying
+        assert_eq!(first[..4], [5, 20, 16, 9]);
+
+        //a constant source always lands on the lower bound
+        let mut zero = rand::rngs::mock::StepRng::new(0, 0);
+        assert_eq!(dice.roll_with(&mut zero), 1);
+        assert_eq!(dice.cur(), 1);
+    }
+
     #[test]
     fn speed_duel() {
         let mut dice1 = Dice::new(DiceType::Speed, 2, 2);
@@ -982,4 +1710,369 @@ mod tests {
         assert!(key_page.stagger() >= -2);
         assert!(key_page.health() == 64 || key_page.health() == 66);
     }
+
+    #[test]
+    fn combat_eval_seeded() {
+        let page = CombatPageBuilder::new()
+            .name("Seeded")
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Slash))),
+                1,
+                20,
+            ))
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Normal(NormalDice::Defense(DefenseDice::Block))),
+                1,
+                20,
+            ))
+            .build();
+        let (first, _, _) = page
+            .clone()
+            .eval_with(page.clone(), &mut crate::rng::seeded(7));
+        let (second, _, _) = page
+            .clone()
+            .eval_with(page.clone(), &mut crate::rng::seeded(7));
+        let first: Vec<i32> = first.iter().map(|x| x.cur()).collect();
+        let second: Vec<i32> = second.iter().map(|x| x.cur()).collect();
+        assert_eq!(first, second);
+
+        //both sides roll their minimum, so every clash is a draw
+        let mut zero = rand::rngs::mock::StepRng::new(0, 0);
+        let (results, _, _) = page.clone().eval_with(page, &mut zero);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].cur(), 0);
+        assert_eq!(results[1].cur(), 0);
+    }
+
+    #[test]
+    fn leftover_one_sided() {
+        let own = CombatPageBuilder::new()
+            .name("Own")
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Slash))),
+                4,
+                4,
+            ))
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Pierce))),
+                6,
+                6,
+            ))
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Normal(NormalDice::Defense(DefenseDice::Evade))),
+                3,
+                3,
+            ))
+            .build();
+        let other = CombatPageBuilder::new()
+            .name("Other")
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Normal(NormalDice::Defense(DefenseDice::Block))),
+                2,
+                2,
+            ))
+            .build();
+        let (results, selfre, otherre) = own.eval(other);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].cur(), 2);
+        assert_eq!(results[1].cur(), 6);
+        assert_eq!(selfre.len(), 1);
+        assert_eq!(otherre.len(), 0);
+
+        let mut target = KeyPageBuilder::new().health(30).stagger(30).build();
+        for result in &results {
+            target.eval(&result.reversed());
+        }
+        assert_eq!(target.health(), 22);
+    }
+
+    #[test]
+    fn page_one_sided() {
+        let page = CombatPageBuilder::new()
+            .name("Unopposed")
+            .dice(
+                Dice::new(
+                    DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Blunt))),
+                    5,
+                    5,
+                )
+                .with_effect(Arc::new(RecoverStagger {
+                    trigger: Trigger::OnHit,
+                    amount: 2,
+                })),
+            )
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Normal(NormalDice::Defense(DefenseDice::Block))),
+                3,
+                3,
+            ))
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Counter(NormalDice::Attack(AttackDice::Slash))),
+                3,
+                3,
+            ))
+            .build();
+        let mut own = KeyPageBuilder::new().health(30).stagger(30).build();
+        own.curstagger = 20;
+        let mut target = KeyPageBuilder::new()
+            .health(30)
+            .stagger(30)
+            .hblunt_resistance(Resistance::Weak)
+            .build();
+        let report = page.one_sided(&mut own, &mut target, &mut crate::rng::seeded(0));
+        assert_eq!(report.records.len(), 1);
+        assert_eq!(report.own_unused.len(), 2);
+        assert_eq!(target.health(), 23);
+        assert_eq!(target.stagger(), 25);
+        assert_eq!(own.stagger(), 22);
+        assert_eq!(own.reserved.len(), 2);
+    }
+
+    #[test]
+    fn reserved_counter() {
+        let counter = CombatPageBuilder::new()
+            .name("Counter")
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Counter(NormalDice::Attack(AttackDice::Slash))),
+                9,
+                9,
+            ))
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Normal(NormalDice::Defense(DefenseDice::Evade))),
+                1,
+                1,
+            ))
+            .build();
+        let attack = CombatPageBuilder::new()
+            .name("Attack")
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Pierce))),
+                4,
+                4,
+            ))
+            .build();
+        let mut rng = crate::rng::seeded(0);
+        let mut own = KeyPageBuilder::new()
+            .speed(Dice::new(DiceType::Speed, 1, 4))
+            .health(30)
+            .stagger(30)
+            .build();
+        let mut other = KeyPageBuilder::new().health(30).stagger(30).build();
+        assert_eq!(own.reserved_slot(), 1);
+
+        //neither die met an opposing die, so both wait on the slot
+        let _ = counter
+            .clone()
+            .clash(CombatPage::default(), &mut own, &mut other, &mut rng);
+        assert_eq!(own.reserved.len(), 2);
+
+        let report = attack.clone().one_sided(&mut other, &mut own, &mut rng);
+        assert_eq!(report.records.len(), 1);
+        assert_eq!(report.records[0].winner, Winner::Other);
+        assert_eq!(own.health(), 30);
+        assert_eq!(other.health(), 21);
+        assert_eq!(own.reserved.len(), 1);
+
+        let _ = counter.clash(CombatPage::default(), &mut own, &mut other, &mut rng);
+        own.end_scene();
+        assert!(own.reserved.is_empty());
+        let _ = attack.one_sided(&mut other, &mut own, &mut rng);
+        assert_eq!(own.health(), 26);
+    }
+
+    #[test]
+    fn reserved_against_leftovers() {
+        let counter = Dice::new(
+            DiceType::Combat(CombatDice::Counter(NormalDice::Attack(AttackDice::Slash))),
+            9,
+            9,
+        );
+        let pierce = Dice::new(
+            DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Pierce))),
+            4,
+            4,
+        );
+        let mut rng = crate::rng::seeded(0);
+        let mut own = KeyPageBuilder::new().health(30).stagger(30).build();
+        let mut other = KeyPageBuilder::new().health(30).stagger(30).build();
+        own.reserve(vec![counter.clone()]);
+
+        //a counter die used one-sided waits instead of clashing the reserved one
+        let page = CombatPageBuilder::new()
+            .dice(counter.clone())
+            .dice(pierce.clone())
+            .build();
+        let report = page.try_one_sided(&mut other, &mut own, &mut rng).unwrap();
+        assert_eq!(report.records.len(), 1);
+        assert_eq!(report.records[0].winner, Winner::Other);
+        assert_eq!(other.reserved.len(), 1);
+        assert!(own.reserved.is_empty());
+
+        //the second die outlasts the opposing page and meets the reserved die
+        own.reserve(vec![counter]);
+        let page = CombatPageBuilder::new()
+            .dice(pierce.clone())
+            .dice(pierce.clone())
+            .build();
+        let single = CombatPageBuilder::new().dice(pierce).build();
+        let report = page.clash(single, &mut other, &mut own, &mut rng);
+        assert_eq!(report.records.len(), 2);
+        assert!(report.records.iter().all(|x| !x.is_unopposed()));
+        assert_eq!(report.records[1].winner, Winner::Other);
+        assert_eq!(own.health(), 30);
+    }
+
+    #[test]
+    fn staggered_loses_reserved() {
+        let dice =
+            |dtype, value| Dice::new(DiceType::Combat(CombatDice::Normal(dtype)), value, value);
+        let block = dice(NormalDice::Defense(DefenseDice::Block), 20);
+        let mut rng = crate::rng::seeded(0);
+        let mut own = KeyPageBuilder::new().health(30).stagger(30).build();
+        let mut other = KeyPageBuilder::new().health(30).stagger(5).build();
+        other.reserve(vec![block.clone()]);
+
+        //the first hit breaks the target before the leftover slash comes
+        let page = CombatPageBuilder::new()
+            .dice(dice(NormalDice::Attack(AttackDice::Slash), 10))
+            .dice(dice(NormalDice::Attack(AttackDice::Slash), 5))
+            .build();
+        let single = CombatPageBuilder::new()
+            .dice(dice(NormalDice::Attack(AttackDice::Slash), 1))
+            .build();
+        let report = page.clash(single, &mut own, &mut other, &mut rng);
+        assert!(other.is_staggered());
+        assert!(report.records[1].is_unopposed());
+        assert_eq!(other.health(), 30 - 10 - 10);
+        assert!(other.reserved.is_empty());
+        other.reserve(vec![block]);
+        assert!(other.reserved.is_empty());
+    }
+
+    #[test]
+    fn combat_errors() {
+        let slash = Dice::new(
+            DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Slash))),
+            1,
+            2,
+        );
+        let counter = Dice::new(
+            DiceType::Combat(CombatDice::Counter(NormalDice::Attack(AttackDice::Slash))),
+            1,
+            2,
+        );
+
+        let err = KeyPageBuilder::new().try_speed(slash.clone()).err();
+        assert_eq!(err, Some(CombatError::NotSpeedDice(slash.dtype().clone())));
+        assert_eq!(
+            KeyPageBuilder::new().health(0).try_build().err(),
+            Some(CombatError::NonPositiveHealth(0))
+        );
+        assert!(KeyPageBuilder::new().health(10).try_build().is_ok());
+        assert_eq!(
+            CombatPageBuilder::new().try_ptype(PageType::Key).err(),
+            Some(CombatError::KeyPageType)
+        );
+        assert_eq!(
+            CombatPageBuilder::new()
+                .dice(slash.clone())
+                .dice(Dice::new(DiceType::Speed, 1, 2))
+                .try_build()
+                .err(),
+            Some(CombatError::SpeedInCombatPage(1))
+        );
+
+        let mut key = KeyPage::default();
+        assert_eq!(
+            key.try_eval(&Dice::new(DiceType::Speed, 1, 1)),
+            Err(CombatError::SpeedResult)
+        );
+        assert_eq!(
+            slash.clone().try_clash(counter.clone()).err(),
+            Some(CombatError::Unrolled)
+        );
+        assert_eq!(
+            counter.clone().try_clash(counter.clone()).err(),
+            Some(CombatError::CounterClash)
+        );
+        assert_eq!(
+            NormalDice::Attack(AttackDice::Slash)
+                .try_combat(-1, &NormalDice::Defense(DefenseDice::Block), 2)
+                .err(),
+            Some(CombatError::Unrolled)
+        );
+
+        let page = CombatPageBuilder::new().dice(counter).build();
+        let err = page
+            .clone()
+            .try_eval_with(page, &mut crate::rng::seeded(0))
+            .err();
+        assert_eq!(err, Some(CombatError::CounterClash));
+        assert_eq!(
+            CombatError::CounterClash.to_string(),
+            "counter dice cannot clash with counter dice"
+        );
+    }
+
+    #[test]
+    fn light_spending() {
+        let mut key = KeyPageBuilder::new().lights(3).build();
+        let page = CombatPageBuilder::new().cost(2).build();
+        assert_eq!(page.cost(), 2);
+        assert!(key.spend_light(&page).is_ok());
+        assert_eq!(
+            key.spend_light(&page).unwrap_err(),
+            CombatError::NotEnoughLight(2, 1)
+        );
+        assert_eq!(key.curlights, 1);
+        assert_eq!(
+            CombatPageBuilder::new().cost(-1).try_build().err(),
+            Some(CombatError::NegativeCost(-1))
+        );
+    }
+
+    #[test]
+    fn stagger_lifecycle() {
+        let mut key = KeyPageBuilder::new()
+            .speed(Dice::new(DiceType::Speed, 1, 4))
+            .health(100)
+            .stagger(10)
+            .hslash_resistance(Resistance::Immune)
+            .build();
+        let slash = |value: i32| Damage {
+            attack: Some(AttackDice::Slash),
+            health: value,
+            stagger: value,
+            recovery: 0,
+        };
+        key.reserve(vec![Dice::new(
+            DiceType::Combat(CombatDice::Counter(NormalDice::Attack(AttackDice::Slash))),
+            1,
+            1,
+        )]);
+
+        key.take(&slash(12));
+        assert!(key.is_staggered());
+        assert_eq!(key.stagger(), 0);
+        assert_eq!(key.health(), 100);
+        assert!(key.gets().is_none());
+        assert!(key.reserved.is_empty());
+
+        //immune no longer helps once staggered
+        key.take(&slash(5));
+        assert_eq!(key.health(), 90);
+        key.take(&Damage {
+            recovery: 4,
+            ..Default::default()
+        });
+        assert_eq!(key.stagger(), 0);
+
+        key.end_scene();
+        assert!(key.is_staggered());
+        assert!(key.get(0).is_none());
+        key.end_scene();
+        assert_eq!(key.stagger_state, StaggerState::Normal);
+        assert_eq!(key.stagger(), 10);
+        assert!(key.get(0).is_some());
+    }
 }
@@ -0,0 +1,202 @@
+// dice power of LOR combat
+
+use crate::object::{AttackDice, CombatDice, CombatPage, DefenseDice, Dice, DiceType, NormalDice};
+
+//power can never push a rolled value under this, a die that naturally
+//rolls lower (0 faces) keeps its natural value instead
+pub const MIN_VALUE: i32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PowerSource {
+    Page,
+    Status,
+    Passive,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DicePower {
+    page: i32,
+    status: i32,
+    passive: i32,
+}
+
+impl DicePower {
+    pub fn get(&self, source: PowerSource) -> i32 {
+        match source {
+            PowerSource::Page => self.page,
+            PowerSource::Status => self.status,
+            PowerSource::Passive => self.passive,
+        }
+    }
+
+    fn slot(&mut self, source: PowerSource) -> &mut i32 {
+        match source {
+            PowerSource::Page => &mut self.page,
+            PowerSource::Status => &mut self.status,
+            PowerSource::Passive => &mut self.passive,
+        }
+    }
+
+    pub fn add(&mut self, source: PowerSource, amount: i32) {
+        *self.slot(source) += amount;
+    }
+
+    pub fn set(&mut self, source: PowerSource, amount: i32) {
+        *self.slot(source) = amount;
+    }
+
+    pub fn clear(&mut self, source: PowerSource) {
+        self.set(source, 0);
+    }
+
+    pub fn total(&self) -> i32 {
+        self.page + self.status + self.passive
+    }
+
+    //final value of a die that landed on `value`
+    pub fn apply(&self, value: i32) -> i32 {
+        let total = self.total();
+        if total == 0 {
+            return value;
+        }
+        (value + total).max(value.min(MIN_VALUE))
+    }
+}
+
+//which dice a modifier reaches, counter dice match by their inner type
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiceFilter {
+    All,
+    Offensive,
+    Defensive,
+    Attack(AttackDice),
+    Defense(DefenseDice),
+    Counter,
+}
+
+impl DiceFilter {
+    pub fn matches(&self, dtype: &DiceType) -> bool {
+        let (dice, counter) = match dtype {
+            DiceType::Speed => return false,
+            DiceType::Combat(CombatDice::Normal(dice)) => (dice, false),
+            DiceType::Combat(CombatDice::Counter(dice)) => (dice, true),
+        };
+        match self {
+            Self::All => true,
+            Self::Offensive => matches!(dice, NormalDice::Attack(_)),
+            Self::Defensive => matches!(dice, NormalDice::Defense(_)),
+            Self::Attack(atype) => *dice == NormalDice::Attack(atype.clone()),
+            Self::Defense(dtype) => *dice == NormalDice::Defense(dtype.clone()),
+            Self::Counter => counter,
+        }
+    }
+}
+
+//"+2 power to slash dice", "-1 power to all dice"
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PowerModifier {
+    pub source: PowerSource,
+    pub amount: i32,
+    pub filter: DiceFilter,
+}
+
+impl PowerModifier {
+    pub fn new(source: PowerSource, amount: i32, filter: DiceFilter) -> Self {
+        Self {
+            source,
+            amount,
+            filter,
+        }
+    }
+
+    pub fn applies(&self, dice: &Dice) -> bool {
+        self.filter.matches(dice.dtype())
+    }
+
+    pub fn apply(&self, dice: &mut Dice) {
+        if self.applies(dice) {
+            dice.add_power(self.source, self.amount);
+        }
+    }
+
+    pub fn apply_page(&self, page: &mut CombatPage) {
+        page.dices.iter_mut().for_each(|x| self.apply(x));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::slash_dice;
+    use crate::object::{CombatPageBuilder, KeyPageBuilder};
+    use crate::probability::ClashDistribution;
+
+    #[test]
+    fn power_sources() {
+        let mut dice = slash_dice(3, 3);
+        dice.add_power(PowerSource::Page, 2);
+        dice.add_power(PowerSource::Status, -1);
+        assert_eq!(dice.power().get(PowerSource::Page), 2);
+        assert_eq!(dice.power().get(PowerSource::Status), -1);
+        assert_eq!(dice.power().get(PowerSource::Passive), 0);
+        assert_eq!(dice.power().total(), 1);
+        assert_eq!(dice.roll(), 4);
+        assert_eq!(dice.raw(), 3);
+    }
+
+    #[test]
+    fn power_clamp() {
+        let mut dice = slash_dice(2, 2);
+        dice.add_power(PowerSource::Status, -5);
+        assert_eq!(dice.roll(), MIN_VALUE);
+
+        let mut zero = slash_dice(0, 0);
+        zero.add_power(PowerSource::Passive, -1);
+        assert_eq!(zero.roll(), 0);
+        zero.add_power(PowerSource::Passive, 3);
+        assert_eq!(zero.roll(), 2);
+    }
+
+    #[test]
+    fn power_filter() {
+        let mut page = CombatPageBuilder::new()
+            .name("Filtered")
+            .dice(slash_dice(4, 4))
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Counter(NormalDice::Defense(DefenseDice::Block))),
+                4,
+                4,
+            ))
+            .build();
+        PowerModifier::new(PowerSource::Page, 2, DiceFilter::Attack(AttackDice::Slash))
+            .apply_page(&mut page);
+        PowerModifier::new(PowerSource::Status, -1, DiceFilter::Defensive).apply_page(&mut page);
+        PowerModifier::new(PowerSource::Passive, 1, DiceFilter::Counter).apply_page(&mut page);
+        assert_eq!(page.dices[0].power().total(), 2);
+        assert_eq!(page.dices[1].power().total(), 0);
+        assert_eq!(page.dices[1].power().get(PowerSource::Passive), 1);
+        assert!(!DiceFilter::All.matches(&DiceType::Speed));
+    }
+
+    #[test]
+    fn power_key_page() {
+        let mut key = KeyPageBuilder::new()
+            .power(PowerModifier::new(
+                PowerSource::Passive,
+                1,
+                DiceFilter::Offensive,
+            ))
+            .build();
+        key.add_power(PowerModifier::new(PowerSource::Page, 2, DiceFilter::All));
+        let mut page = CombatPageBuilder::new().dice(slash_dice(1, 1)).build();
+        key.empower(&mut page);
+        assert_eq!(page.dices[0].power().total(), 3);
+
+        key.clear_power(PowerSource::Page);
+        assert_eq!(key.power.len(), 1);
+
+        //power shifts the exact odds as well
+        let dist = ClashDistribution::of(&page.dices[0], &slash_dice(4, 4));
+        assert_eq!(dist.draw(), 1.0);
+    }
+}
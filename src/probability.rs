@@ -0,0 +1,205 @@
+// exact clash odds of LOR combat
+
+use crate::object::{CombatPage, Dice, DiceType, Winner};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClashOutcome {
+    pub winner: Winner,
+    pub dtype: DiceType,
+    pub value: i32,
+}
+
+//every distinct result of `own - other`, with its chance
+#[derive(Clone, Debug)]
+pub struct ClashDistribution {
+    outcomes: Vec<(ClashOutcome, u64)>,
+    total: u64,
+}
+
+impl ClashDistribution {
+    //walks both min..=max ranges, every pair of faces is equally likely
+    pub fn of(own: &Dice, other: &Dice) -> Self {
+        let (own_min, own_max) = own.range();
+        let (other_min, other_max) = other.range();
+        let mut counts: Vec<(ClashOutcome, u64)> = Vec::new();
+        for own_value in own_min..=own_max {
+            for other_value in other_min..=other_max {
+                let mut lhs = own.clone();
+                let mut rhs = other.clone();
+                lhs.fix(own_value);
+                rhs.fix(other_value);
+                let result = lhs - rhs;
+                let outcome = ClashOutcome {
+                    winner: result.winner(),
+                    dtype: result.dtype().clone(),
+                    value: result.cur(),
+                };
+                match counts.iter_mut().find(|(x, _)| *x == outcome) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((outcome, 1)),
+                }
+            }
+        }
+
+        let total = counts.iter().map(|(_, count)| count).sum();
+        Self {
+            outcomes: counts,
+            total,
+        }
+    }
+
+    pub fn outcomes(&self) -> impl Iterator<Item = (&ClashOutcome, f64)> + '_ {
+        self.outcomes
+            .iter()
+            .map(|(outcome, count)| (outcome, *count as f64 / self.total as f64))
+    }
+
+    pub fn probability(&self, winner: Winner) -> f64 {
+        self.chance(|outcome| outcome.winner == winner)
+    }
+
+    //summed over counts first so certain events come out as exactly 1.0
+    pub fn chance<F: Fn(&ClashOutcome) -> bool>(&self, pred: F) -> f64 {
+        let hits: u64 = self
+            .outcomes
+            .iter()
+            .filter(|(outcome, _)| pred(outcome))
+            .map(|(_, count)| count)
+            .sum();
+        hits as f64 / self.total as f64
+    }
+
+    pub fn win(&self) -> f64 {
+        self.probability(Winner::Own)
+    }
+
+    pub fn lose(&self) -> f64 {
+        self.probability(Winner::Other)
+    }
+
+    pub fn draw(&self) -> f64 {
+        self.probability(Winner::Draw)
+    }
+
+    //expected signed value of the result die
+    pub fn mean(&self) -> f64 {
+        self.outcomes()
+            .map(|(outcome, p)| outcome.value as f64 * p)
+            .sum()
+    }
+}
+
+//dice of two pages clash pairwise in order, leftover dice never clash
+#[derive(Clone, Debug)]
+pub struct PageDistribution {
+    clashes: Vec<ClashDistribution>,
+}
+
+impl PageDistribution {
+    pub fn of(own: &CombatPage, other: &CombatPage) -> Self {
+        Self {
+            clashes: own
+                .gets()
+                .iter()
+                .zip(other.gets().iter())
+                .map(|(x, y)| ClashDistribution::of(x, y))
+                .collect(),
+        }
+    }
+
+    pub fn clashes(&self) -> &[ClashDistribution] {
+        &self.clashes
+    }
+
+    pub fn get(&self, index: usize) -> Option<&ClashDistribution> {
+        self.clashes.get(index)
+    }
+
+    //chance of exactly k clashes going to `winner`, for k in 0..=clashes
+    pub fn count(&self, winner: Winner) -> Vec<f64> {
+        let mut dist = vec![1.0];
+        for clash in &self.clashes {
+            let p = clash.probability(winner);
+            let mut next = vec![0.0; dist.len() + 1];
+            for (k, q) in dist.iter().enumerate() {
+                next[k] += q * (1.0 - p);
+                next[k + 1] += q * p;
+            }
+            dist = next;
+        }
+        dist
+    }
+
+    pub fn wins(&self) -> Vec<f64> {
+        self.count(Winner::Own)
+    }
+
+    pub fn wins_at_least(&self, n: usize) -> f64 {
+        self.wins().iter().skip(n).sum()
+    }
+
+    pub fn losses_at_least(&self, n: usize) -> f64 {
+        self.count(Winner::Other).iter().skip(n).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::slash_dice;
+    use crate::object::{CombatDice, CombatPageBuilder, DefenseDice, NormalDice, PageRarity};
+
+    #[test]
+    fn clash_symmetric() {
+        let dist = ClashDistribution::of(&slash_dice(1, 2), &slash_dice(1, 2));
+        assert_eq!(dist.win(), 0.25);
+        assert_eq!(dist.lose(), 0.25);
+        assert_eq!(dist.draw(), 0.5);
+        assert_eq!(dist.outcomes().count(), 3);
+        assert!((dist.win() + dist.lose() + dist.draw() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn clash_block() {
+        let block = Dice::new(
+            DiceType::Combat(CombatDice::Normal(NormalDice::Defense(DefenseDice::Block))),
+            4,
+            6,
+        );
+        let dist = ClashDistribution::of(&slash_dice(2, 3), &block);
+        assert_eq!(dist.lose(), 1.0);
+        for (outcome, _) in dist.outcomes() {
+            assert_eq!(
+                outcome.dtype,
+                DiceType::Combat(CombatDice::Normal(NormalDice::Defense(DefenseDice::Block)))
+            );
+            assert!(outcome.value >= -4 && outcome.value <= -1);
+        }
+        assert_eq!(dist.chance(|x| x.value == -4), 1.0 / 6.0);
+    }
+
+    #[test]
+    fn page_wins_at_least() {
+        let own = CombatPageBuilder::new()
+            .name("Own")
+            .rarity(PageRarity::Paperback)
+            .dice(slash_dice(1, 2))
+            .dice(slash_dice(1, 2))
+            .dice(slash_dice(1, 2))
+            .build();
+        let other = CombatPageBuilder::new()
+            .name("Other")
+            .rarity(PageRarity::Paperback)
+            .dice(slash_dice(1, 1))
+            .dice(slash_dice(1, 1))
+            .dice(slash_dice(1, 1))
+            .dice(slash_dice(1, 1))
+            .build();
+        let dist = PageDistribution::of(&own, &other);
+        assert_eq!(dist.clashes().len(), 3);
+        assert_eq!(dist.wins(), vec![0.125, 0.375, 0.375, 0.125]);
+        assert_eq!(dist.wins_at_least(2), 0.5);
+        assert_eq!(dist.wins_at_least(0), 1.0);
+        assert_eq!(dist.losses_at_least(1), 0.0);
+    }
+}
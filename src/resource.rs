@@ -0,0 +1,229 @@
+// character-unique stacking resources of LOR combat
+
+use crate::damage::DamageModifiers;
+use crate::object::{Dice, KeyPage};
+use crate::status::{self, StatusEffect, StatusKind};
+use std::sync::Arc;
+
+//what a resource keeps when the scene ends
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Decay {
+    //kept until spent
+    #[default]
+    Keep,
+    Clear,
+    Lose(i32),
+    Halve,
+}
+
+impl Decay {
+    pub fn apply(&self, stacks: i32) -> i32 {
+        match self {
+            Self::Keep => stacks,
+            Self::Clear => 0,
+            Self::Lose(x) => (stacks - x).max(0),
+            Self::Halve => stacks / 2,
+        }
+    }
+}
+
+//a keyworded stack count declared by data rather than code, e.g. Smoke:
+//cap 10, +5% damage dealt and taken per stack, loses 1 per scene
+pub struct Resource {
+    pub name: String,
+    pub kind: StatusKind,
+    pub cap: Option<i32>,
+    pub decay: Decay,
+    //layers per stack
+    pub outgoing: DamageModifiers,
+    pub incoming: DamageModifiers,
+    //health lost per stack whenever the owner rolls an offensive die
+    pub action_damage: i32,
+}
+
+impl StatusEffect for Resource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> StatusKind {
+        self.kind
+    }
+
+    fn cap(&self) -> Option<i32> {
+        self.cap
+    }
+
+    fn outgoing(&self, stacks: i32) -> DamageModifiers {
+        self.outgoing.times(stacks)
+    }
+
+    fn incoming(&self, stacks: i32) -> DamageModifiers {
+        self.incoming.times(stacks)
+    }
+
+    fn dice_roll(&self, stacks: &mut i32, owner: &mut KeyPage, dice: &mut Dice) {
+        if self.action_damage != 0 && dice.is_offensive() {
+            status::lose_health(owner, self.action_damage * *stacks);
+        }
+    }
+
+    fn scene_end(&self, stacks: &mut i32, _owner: &mut KeyPage) {
+        *stacks = self.decay.apply(*stacks);
+    }
+}
+
+pub struct ResourceBuilder {
+    name: String,
+    kind: StatusKind,
+    cap: Option<i32>,
+    decay: Decay,
+    outgoing: DamageModifiers,
+    incoming: DamageModifiers,
+    action_damage: i32,
+}
+
+impl ResourceBuilder {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: StatusKind::Buff,
+            cap: None,
+            decay: Decay::Keep,
+            outgoing: DamageModifiers::default(),
+            incoming: DamageModifiers::default(),
+            action_damage: 0,
+        }
+    }
+
+    pub fn kind(mut self, kind: StatusKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn cap(mut self, cap: i32) -> Self {
+        self.cap = Some(cap);
+        self
+    }
+
+    pub fn decay(mut self, decay: Decay) -> Self {
+        self.decay = decay;
+        self
+    }
+
+    pub fn outgoing(mut self, outgoing: DamageModifiers) -> Self {
+        self.outgoing = outgoing;
+        self
+    }
+
+    pub fn incoming(mut self, incoming: DamageModifiers) -> Self {
+        self.incoming = incoming;
+        self
+    }
+
+    pub fn action_damage(mut self, action_damage: i32) -> Self {
+        self.action_damage = action_damage;
+        self
+    }
+
+    pub fn build(self) -> Arc<Resource> {
+        Arc::new(Resource {
+            name: self.name,
+            kind: self.kind,
+            cap: self.cap,
+            decay: self.decay,
+            outgoing: self.outgoing,
+            incoming: self.incoming,
+            action_damage: self.action_damage,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::damage::DamageModifier;
+    use crate::effect::{effect, GainStatus, Trigger};
+    use crate::fixtures::slash_dice;
+    use crate::object::{CombatPageBuilder, KeyPageBuilder};
+    use crate::status::Strength;
+
+    fn smoke() -> Arc<Resource> {
+        let percent = DamageModifier {
+            percent: 10,
+            ..Default::default()
+        };
+        let both = DamageModifiers {
+            health: percent,
+            stagger: percent,
+        };
+        ResourceBuilder::new("Smoke")
+            .cap(10)
+            .decay(Decay::Lose(1))
+            .outgoing(both)
+            .incoming(both)
+            .build()
+    }
+
+    #[test]
+    fn resource_cap_decay() {
+        let mut key = KeyPageBuilder::new().health(50).stagger(50).build();
+        key.statuses.add(smoke(), 7);
+        key.statuses.add(smoke(), 7);
+        assert_eq!(key.statuses.stacks("Smoke"), 10);
+        assert!(key.statuses.spend("Smoke", 4));
+        assert!(!key.statuses.spend("Smoke", 7));
+        assert_eq!(key.statuses.stacks("Smoke"), 6);
+        key.end_scene();
+        assert_eq!(key.statuses.stacks("Smoke"), 5);
+        //next scene stacks still respect the cap once they become active
+        key.statuses.add_next(smoke(), 8);
+        key.start_scene();
+        assert_eq!(key.statuses.stacks("Smoke"), 10);
+        assert_eq!(Decay::Halve.apply(5), 2);
+        assert_eq!(Decay::Clear.apply(5), 0);
+    }
+
+    #[test]
+    fn resource_in_clash() {
+        let mut own = KeyPageBuilder::new().health(50).stagger(50).build();
+        let mut other = KeyPageBuilder::new().health(50).stagger(50).build();
+        let fairy = ResourceBuilder::new("Fairy")
+            .kind(StatusKind::Debuff)
+            .action_damage(1)
+            .decay(Decay::Clear)
+            .build();
+        own.statuses.add(smoke(), 5);
+        other.statuses.add(fairy, 2);
+
+        //spend 3 charge for +3 power on use
+        let charge = ResourceBuilder::new("Charge").cap(20).build();
+        own.statuses.add(charge.clone(), 4);
+        let page = CombatPageBuilder::new()
+            .dice(slash_dice(5, 5).with_effect(Arc::new(GainStatus {
+                trigger: Trigger::OnUse,
+                status: charge,
+                stacks: 4,
+            })))
+            .effect(effect(Trigger::OnUse, "Spend 3 Charge", |ctx| {
+                if ctx.owner.statuses.spend("Charge", 3) {
+                    ctx.owner.statuses.add(Arc::new(Strength), 3);
+                }
+            }))
+            .build();
+        let other_page = CombatPageBuilder::new().dice(slash_dice(1, 1)).build();
+        let report = page.clash(other_page, &mut own, &mut other, &mut crate::rng::seeded(0));
+
+        //page effects fire before the die gains charge
+        assert_eq!(own.statuses.stacks("Charge"), 4 - 3 + 4);
+        assert_eq!(report.records[0].own_value(), Some(8));
+        //8 x1.5 from 5 smoke, fairy costs 2 on the slash roll
+        assert_eq!(other.health(), 50 - 12 - 2);
+        //smoke also raises damage taken
+        let _ = CombatPageBuilder::new()
+            .dice(slash_dice(4, 4))
+            .build()
+            .one_sided(&mut other, &mut own, &mut crate::rng::seeded(0));
+        assert_eq!(own.health(), 44);
+    }
+}
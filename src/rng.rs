@@ -0,0 +1,28 @@
+// random source of LOR combat
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+//every roll in the crate accepts any rand::Rng, so a custom source only
+//needs to implement RngCore; the seeded default below is what replays use.
+//a fixed algorithm, unlike StdRng, so a seed keeps replaying across rand
+//releases and platforms
+pub type SeededRng = ChaCha8Rng;
+
+pub fn seeded(seed: u64) -> SeededRng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+//seed drawn from the thread rng, for callers who want to log it and replay later
+pub fn random_seed() -> u64 {
+    rand::thread_rng().gen()
+}
+
+//one SplitMix64 step, spreads nearby seeds over the whole range so streams
+//derived from them do not line up
+pub fn mix(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
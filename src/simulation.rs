@@ -0,0 +1,473 @@
+// monte carlo clash runs of LOR combat
+
+use crate::clash;
+use crate::damage::HitBreakdown;
+use crate::object::{CombatPage, KeyPage, Winner};
+use crate::rng;
+use std::collections::BTreeMap;
+use std::thread;
+
+//trials are split into fixed chunks with their own seed, so a report
+//only depends on the seed and never on the number of threads
+const CHUNK: u64 = 256;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+    pub count: u64,
+    pub sum: i64,
+    pub sum_sq: i64,
+    pub histogram: BTreeMap<i32, u64>,
+}
+
+impl Summary {
+    pub fn record(&mut self, value: i32) {
+        self.count += 1;
+        self.sum += value as i64;
+        self.sum_sq += (value as i64) * (value as i64);
+        *self.histogram.entry(value).or_insert(0) += 1;
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.count += other.count;
+        self.sum += other.sum;
+        self.sum_sq += other.sum_sq;
+        for (value, count) in &other.histogram {
+            *self.histogram.entry(*value).or_insert(0) += count;
+        }
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        self.sum as f64 / self.count as f64
+    }
+
+    //population variance
+    pub fn variance(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        self.sum_sq as f64 / self.count as f64 - mean * mean
+    }
+
+    pub fn min(&self) -> Option<i32> {
+        self.histogram.keys().next().copied()
+    }
+
+    pub fn max(&self) -> Option<i32> {
+        self.histogram.keys().next_back().copied()
+    }
+}
+
+//outcome counts of one dice pair, from the own page's point of view
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DieStats {
+    pub wins: u64,
+    pub losses: u64,
+    pub draws: u64,
+}
+
+impl DieStats {
+    pub fn record(&mut self, winner: Winner) {
+        match winner {
+            Winner::Own => self.wins += 1,
+            Winner::Other => self.losses += 1,
+            Winner::Draw => self.draws += 1,
+        }
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.draws += other.draws;
+    }
+
+    pub fn total(&self) -> u64 {
+        self.wins + self.losses + self.draws
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        rate(self.wins, self.total())
+    }
+
+    pub fn lose_rate(&self) -> f64 {
+        rate(self.losses, self.total())
+    }
+
+    pub fn draw_rate(&self) -> f64 {
+        rate(self.draws, self.total())
+    }
+}
+
+//damage one side dealt to the other over all trials
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SideStats {
+    pub health_dealt: Summary,
+    pub stagger_dealt: Summary,
+    pub staggered: u64,
+}
+
+impl SideStats {
+    pub fn merge(&mut self, other: &Self) {
+        self.health_dealt.merge(&other.health_dealt);
+        self.stagger_dealt.merge(&other.stagger_dealt);
+        self.staggered += other.staggered;
+    }
+
+    //chance the opposing key page ends the clash staggered
+    pub fn stagger_chance(&self) -> f64 {
+        rate(self.staggered, self.health_dealt.count)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SimulationReport {
+    pub seed: u64,
+    pub trials: u64,
+    pub dice: Vec<DieStats>,
+    pub own: SideStats,
+    pub other: SideStats,
+}
+
+impl SimulationReport {
+    pub fn merge(&mut self, other: &Self) {
+        self.trials += other.trials;
+        if self.dice.len() < other.dice.len() {
+            self.dice.resize(other.dice.len(), DieStats::default());
+        }
+        for (x, y) in self.dice.iter_mut().zip(other.dice.iter()) {
+            x.merge(y);
+        }
+        self.own.merge(&other.own);
+        self.other.merge(&other.other);
+    }
+}
+
+#[derive(Clone)]
+pub struct Simulation {
+    own_page: CombatPage,
+    other_page: CombatPage,
+    own_key: KeyPage,
+    other_key: KeyPage,
+    trials: u64,
+    seed: u64,
+    threads: usize,
+}
+
+impl Simulation {
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn run(&self) -> SimulationReport {
+        let chunks = self.trials.div_ceil(CHUNK);
+        let threads = (self.threads as u64).clamp(1, chunks.max(1));
+
+        let mut report = SimulationReport {
+            seed: self.seed,
+            ..Default::default()
+        };
+        let parts: Vec<SimulationReport> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|worker| {
+                    scope.spawn(move || {
+                        let mut part = SimulationReport::default();
+                        let mut chunk = worker;
+                        while chunk < chunks {
+                            part.merge(&self.run_chunk(chunk));
+                            chunk += threads;
+                        }
+                        part
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+        for part in &parts {
+            report.merge(part);
+        }
+        report
+    }
+
+    fn run_chunk(&self, chunk: u64) -> SimulationReport {
+        //seed + chunk would replay chunk 0 of seed + 1 as chunk 1 of seed
+        let mut rng = rng::seeded(rng::mix(rng::mix(self.seed) ^ chunk));
+        let start = chunk * CHUNK;
+        let end = (start + CHUNK).min(self.trials);
+        let mut report = SimulationReport::default();
+        for _ in start..end {
+            self.trial(&mut rng, &mut report);
+        }
+        report
+    }
+
+    fn trial(&self, rng: &mut rng::SeededRng, report: &mut SimulationReport) {
+        let mut own_key = self.own_key.clone();
+        let mut other_key = self.other_key.clone();
+        let clash = clash::resolve(
+            &mut own_key,
+            self.own_page.clone(),
+            &mut other_key,
+            self.other_page.clone(),
+            rng,
+        );
+
+        if report.dice.len() < clash.records.len() {
+            report.dice.resize(clash.records.len(), DieStats::default());
+        }
+        //one-sided hits are not won, there was nothing to win against
+        for (index, record) in clash.records.iter().enumerate() {
+            if !record.is_unopposed() {
+                report.dice[index].record(record.winner);
+            }
+        }
+
+        report.trials += 1;
+        let own_dealt = clash.records.iter().map(|x| &x.other_breakdown);
+        let other_dealt = clash.records.iter().map(|x| &x.own_breakdown);
+        record_side(&mut report.own, own_dealt, &other_key);
+        record_side(&mut report.other, other_dealt, &own_key);
+    }
+}
+
+//damage is summed from the hits themselves, so the target's recovery and
+//a stagger break running out of stagger to take do not skew it
+fn record_side<'a, I>(side: &mut SideStats, dealt: I, target: &KeyPage)
+where
+    I: Iterator<Item = &'a HitBreakdown>,
+{
+    let (health, stagger) = dealt.fold((0, 0), |(health, stagger), x| {
+        (health + x.health.value, stagger + x.stagger.value)
+    });
+    side.health_dealt.record(health);
+    side.stagger_dealt.record(stagger);
+    if target.is_staggered() {
+        side.staggered += 1;
+    }
+}
+
+fn rate(count: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    count as f64 / total as f64
+}
+
+#[derive(Clone)]
+pub struct SimulationBuilder {
+    own_page: CombatPage,
+    other_page: CombatPage,
+    own_key: KeyPage,
+    other_key: KeyPage,
+    trials: u64,
+    seed: Option<u64>,
+    threads: usize,
+}
+
+impl SimulationBuilder {
+    pub fn new(own_page: CombatPage, other_page: CombatPage) -> Self {
+        Self {
+            own_page,
+            other_page,
+            own_key: KeyPage::default(),
+            other_key: KeyPage::default(),
+            trials: 10000,
+            seed: None,
+            threads: 1,
+        }
+    }
+
+    pub fn own_key(mut self, key: KeyPage) -> Self {
+        self.own_key = key;
+        self
+    }
+
+    pub fn other_key(mut self, key: KeyPage) -> Self {
+        self.other_key = key;
+        self
+    }
+
+    pub fn trials(mut self, trials: u64) -> Self {
+        self.trials = trials;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    //one worker per available core
+    pub fn parallel(self) -> Self {
+        let threads = thread::available_parallelism()
+            .map(|x| x.get())
+            .unwrap_or(1);
+        self.threads(threads)
+    }
+
+    pub fn build(self) -> Simulation {
+        Simulation {
+            own_page: self.own_page,
+            other_page: self.other_page,
+            own_key: self.own_key,
+            other_key: self.other_key,
+            trials: self.trials,
+            seed: self.seed.unwrap_or_else(rng::random_seed),
+            threads: self.threads,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{
+        AttackDice, CombatDice, CombatPageBuilder, DefenseDice, Dice, DiceType, KeyPageBuilder,
+        NormalDice, PageRarity,
+    };
+
+    fn pages() -> (CombatPage, CombatPage) {
+        let own = CombatPageBuilder::new()
+            .name("Own")
+            .rarity(PageRarity::Paperback)
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Slash))),
+                1,
+                8,
+            ))
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Blunt))),
+                10,
+                10,
+            ))
+            .build();
+        let other = CombatPageBuilder::new()
+            .name("Other")
+            .rarity(PageRarity::Paperback)
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Pierce))),
+                1,
+                8,
+            ))
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Normal(NormalDice::Defense(DefenseDice::Block))),
+                2,
+                2,
+            ))
+            .build();
+        (own, other)
+    }
+
+    #[test]
+    fn simulation_reproducible() {
+        let (own, other) = pages();
+        let key = KeyPageBuilder::new().health(1000).stagger(1000).build();
+        let single = SimulationBuilder::new(own.clone(), other.clone())
+            .own_key(key.clone())
+            .other_key(key.clone())
+            .trials(1000)
+            .seed(3)
+            .build()
+            .run();
+        let multi = SimulationBuilder::new(own, other)
+            .own_key(key.clone())
+            .other_key(key)
+            .trials(1000)
+            .seed(3)
+            .threads(4)
+            .build()
+            .run();
+        assert_eq!(single, multi);
+        assert_eq!(single.trials, 1000);
+        assert_eq!(single.seed, 3);
+    }
+
+    #[test]
+    fn simulation_adjacent_seeds() {
+        let (own, other) = pages();
+        let key = KeyPageBuilder::new().health(1000).stagger(1000).build();
+        let simulation = |seed| {
+            SimulationBuilder::new(own.clone(), other.clone())
+                .own_key(key.clone())
+                .other_key(key.clone())
+                .trials(CHUNK * 2)
+                .seed(seed)
+                .build()
+        };
+        let (first, second) = (simulation(5), simulation(6));
+        assert_ne!(first.run_chunk(1), second.run_chunk(0));
+        assert_ne!(first.run_chunk(0), second.run_chunk(1));
+    }
+
+    #[test]
+    fn simulation_hits_only() {
+        let dice =
+            |dtype, value| Dice::new(DiceType::Combat(CombatDice::Normal(dtype)), value, value);
+        let own = CombatPageBuilder::new()
+            .dice(dice(NormalDice::Attack(AttackDice::Slash), 5))
+            .dice(dice(NormalDice::Attack(AttackDice::Slash), 3))
+            .build();
+        let other = CombatPageBuilder::new()
+            .dice(dice(NormalDice::Defense(DefenseDice::Evade), 9))
+            .build();
+        let key = KeyPageBuilder::new().health(100).stagger(20).build();
+        let mut hurt = key.clone();
+        hurt.curstagger = 10;
+        let report = SimulationBuilder::new(own, other)
+            .own_key(key)
+            .other_key(hurt)
+            .trials(100)
+            .seed(1)
+            .build()
+            .run();
+
+        //the evade wins, the leftover slash hits one-sided
+        assert_eq!(report.dice[0].losses, 100);
+        assert_eq!(report.dice[1].total(), 0);
+        //9 recovered stagger is not negative damage
+        assert_eq!(report.own.stagger_dealt.min(), Some(3));
+        assert_eq!(report.own.health_dealt.max(), Some(3));
+        assert_eq!(report.own.stagger_chance(), 0.0);
+    }
+
+    #[test]
+    fn simulation_stats() {
+        let (own, other) = pages();
+        let key = KeyPageBuilder::new().health(100).stagger(8).build();
+        let report = SimulationBuilder::new(own, other)
+            .own_key(key.clone())
+            .other_key(key)
+            .trials(2000)
+            .seed(11)
+            .threads(2)
+            .build()
+            .run();
+
+        assert_eq!(report.dice.len(), 2);
+        let first = &report.dice[0];
+        assert_eq!(first.total(), 2000);
+        assert!((first.win_rate() - first.lose_rate()).abs() < 0.1);
+        assert!((first.draw_rate() - 0.125).abs() < 0.05);
+
+        //blunt 10 always beats block 2 for 8
+        assert_eq!(report.dice[1].win_rate(), 1.0);
+        assert!(report.own.health_dealt.min().unwrap() >= 8);
+        //a slash for the full 8 staggers first, so the blunt hit lands doubled
+        assert_eq!(report.own.health_dealt.max(), Some(24));
+        assert_eq!(report.own.stagger_chance(), 1.0);
+        assert_eq!(report.other.health_dealt.max(), Some(8));
+        assert!(report.other.health_dealt.variance() > 0.0);
+        let histogram: u64 = report.own.health_dealt.histogram.values().sum();
+        assert_eq!(histogram, 2000);
+    }
+}
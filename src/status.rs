@@ -0,0 +1,784 @@
+// status effects of LOR combat
+
+use crate::damage::{DamageModifier, DamageModifiers};
+use crate::object::{Dice, DiceType, KeyPage};
+use crate::power::DiceFilter;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StatusKind {
+    Buff,
+    Debuff,
+}
+
+//every hook gets the stacks of its own status and may change them, a
+//status whose stacks reach 0 is dropped once the hook returns
+pub trait StatusEffect: Send + Sync {
+    //statuses are stacked by name, "Bleed" inflicted twice is one entry
+    fn name(&self) -> &str;
+
+    fn kind(&self) -> StatusKind;
+
+    //power this status gives a rolled die of `dtype`, applied as
+    //PowerSource::Status before any dice_roll hook runs
+    fn power(&self, _stacks: i32, _dtype: &DiceType) -> i32 {
+        0
+    }
+
+    //most stacks the owner can hold, None for no limit
+    fn cap(&self) -> Option<i32> {
+        None
+    }
+
+    //layers added to every hit the owner deals while active
+    fn outgoing(&self, _stacks: i32) -> DamageModifiers {
+        DamageModifiers::default()
+    }
+
+    //layers added to every hit the owner takes while active
+    fn incoming(&self, _stacks: i32) -> DamageModifiers {
+        DamageModifiers::default()
+    }
+
+    fn scene_start(&self, _stacks: &mut i32, _owner: &mut KeyPage) {}
+
+    //the owner rolled a combat die, before it is compared
+    fn dice_roll(&self, _stacks: &mut i32, _owner: &mut KeyPage, _dice: &mut Dice) {}
+
+    fn on_hit(&self, _stacks: &mut i32, _owner: &mut KeyPage, _target: &mut KeyPage) {}
+
+    fn on_hit_taken(&self, _stacks: &mut i32, _owner: &mut KeyPage, _attacker: &mut KeyPage) {}
+
+    //statuses last for the scene they are active in unless they say otherwise
+    fn scene_end(&self, stacks: &mut i32, _owner: &mut KeyPage) {
+        *stacks = 0;
+    }
+}
+
+pub type StatusRef = Arc<dyn StatusEffect>;
+
+#[derive(Clone)]
+pub struct Status {
+    pub effect: StatusRef,
+    //active this scene
+    pub stacks: i32,
+    //become active when the next scene starts
+    pub next: i32,
+}
+
+impl Status {
+    pub fn name(&self) -> &str {
+        self.effect.name()
+    }
+
+    //active and pending stacks are each held to the cap
+    fn limit(&mut self) {
+        if let Some(cap) = self.effect.cap() {
+            self.stacks = self.stacks.min(cap);
+            self.next = self.next.min(cap);
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct StatusContainer {
+    statuses: Vec<Status>,
+}
+
+impl StatusContainer {
+    fn entry(&mut self, effect: StatusRef) -> &mut Status {
+        match self.statuses.iter().position(|x| x.name() == effect.name()) {
+            Some(index) => &mut self.statuses[index],
+            None => {
+                self.statuses.push(Status {
+                    effect,
+                    stacks: 0,
+                    next: 0,
+                });
+                self.statuses.last_mut().unwrap()
+            }
+        }
+    }
+
+    //active right away, for the rest of this scene
+    pub fn add(&mut self, effect: StatusRef, stacks: i32) {
+        let entry = self.entry(effect);
+        entry.stacks += stacks;
+        entry.limit();
+    }
+
+    //"next scene" application, held until start_scene
+    pub fn add_next(&mut self, effect: StatusRef, stacks: i32) {
+        let entry = self.entry(effect);
+        entry.next += stacks;
+        entry.limit();
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Status> {
+        self.statuses.iter().find(|x| x.name() == name)
+    }
+
+    pub fn stacks(&self, name: &str) -> i32 {
+        self.get(name).map(|x| x.stacks).unwrap_or(0)
+    }
+
+    pub fn next_stacks(&self, name: &str) -> i32 {
+        self.get(name).map(|x| x.next).unwrap_or(0)
+    }
+
+    //lower active stacks, never under 0
+    pub fn reduce(&mut self, name: &str, amount: i32) {
+        if let Some(status) = self.statuses.iter_mut().find(|x| x.name() == name) {
+            status.stacks = (status.stacks - amount).max(0);
+        }
+        self.prune();
+    }
+
+    //use up stacks for an effect, all or nothing
+    pub fn spend(&mut self, name: &str, amount: i32) -> bool {
+        if self.stacks(name) < amount {
+            return false;
+        }
+        self.reduce(name, amount);
+        true
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Status> {
+        let index = self.statuses.iter().position(|x| x.name() == name)?;
+        Some(self.statuses.remove(index))
+    }
+
+    //status power every active status gives a die of `dtype`
+    pub fn power(&self, dtype: &DiceType) -> i32 {
+        self.statuses
+            .iter()
+            .filter(|x| x.stacks > 0)
+            .map(|x| x.effect.power(x.stacks, dtype))
+            .sum()
+    }
+
+    pub fn outgoing(&self) -> DamageModifiers {
+        self.statuses
+            .iter()
+            .filter(|x| x.stacks > 0)
+            .fold(DamageModifiers::default(), |acc, x| {
+                acc + x.effect.outgoing(x.stacks)
+            })
+    }
+
+    pub fn incoming(&self) -> DamageModifiers {
+        self.statuses
+            .iter()
+            .filter(|x| x.stacks > 0)
+            .fold(DamageModifiers::default(), |acc, x| {
+                acc + x.effect.incoming(x.stacks)
+            })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Status> {
+        self.statuses.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.statuses.is_empty()
+    }
+
+    fn merge(&mut self, other: Self) {
+        for status in other.statuses {
+            let entry = self.entry(status.effect);
+            entry.stacks += status.stacks;
+            entry.next += status.next;
+            entry.limit();
+        }
+    }
+
+    fn prune(&mut self) {
+        self.statuses.retain(|x| x.stacks > 0 || x.next > 0);
+    }
+
+    fn promote(&mut self) {
+        for status in self.statuses.iter_mut() {
+            status.stacks += status.next;
+            status.next = 0;
+            status.limit();
+        }
+    }
+}
+
+//run `f` on every active status of `owner`; the container is taken out of
+//the key page meanwhile so hooks can touch it, statuses inflicted by the
+//hooks themselves are merged back afterwards
+pub(crate) fn each<F>(owner: &mut KeyPage, mut f: F)
+where
+    F: FnMut(&dyn StatusEffect, &mut i32, &mut KeyPage),
+{
+    if owner.statuses.is_empty() {
+        return;
+    }
+    let mut container = std::mem::take(&mut owner.statuses);
+    for status in container.statuses.iter_mut() {
+        if status.stacks > 0 {
+            f(&*status.effect, &mut status.stacks, owner);
+        }
+    }
+    let added = std::mem::replace(&mut owner.statuses, container);
+    owner.statuses.merge(added);
+    owner.statuses.prune();
+}
+
+pub(crate) fn scene_start(owner: &mut KeyPage) {
+    owner.statuses.promote();
+    each(owner, |status, stacks, owner| {
+        status.scene_start(stacks, owner)
+    });
+}
+
+pub(crate) fn scene_end(owner: &mut KeyPage) {
+    each(owner, |status, stacks, owner| {
+        status.scene_end(stacks, owner)
+    });
+}
+
+pub(crate) fn dice_roll(owner: &mut KeyPage, dice: &mut Dice) {
+    dice.set_status_power(owner.statuses.power(dice.dtype()));
+    each(owner, |status, stacks, owner| {
+        status.dice_roll(stacks, owner, dice)
+    });
+}
+
+pub(crate) fn hit(attacker: &mut KeyPage, target: &mut KeyPage) {
+    each(attacker, |status, stacks, owner| {
+        status.on_hit(stacks, owner, target)
+    });
+    each(target, |status, stacks, owner| {
+        status.on_hit_taken(stacks, owner, attacker)
+    });
+}
+
+//health loss from a status is not a hit: no resistance and no damage
+//layers apply, and it never touches stagger
+pub(crate) fn lose_health(owner: &mut KeyPage, amount: i32) {
+    let breakdown = owner.pipeline.run(amount, &DamageModifier::default(), 1.0);
+    owner.curhealth -= breakdown.value;
+}
+
+//rolling an offensive die deals damage equal to stacks, then a third of
+//the stacks is lost
+pub struct Bleed;
+
+impl StatusEffect for Bleed {
+    fn name(&self) -> &str {
+        "Bleed"
+    }
+
+    fn kind(&self) -> StatusKind {
+        StatusKind::Debuff
+    }
+
+    fn dice_roll(&self, stacks: &mut i32, owner: &mut KeyPage, dice: &mut Dice) {
+        if !dice.is_offensive() {
+            return;
+        }
+        lose_health(owner, *stacks);
+        *stacks = *stacks * 2 / 3;
+    }
+
+    //bleed is kept across scenes, only rolling uses it up
+    fn scene_end(&self, _stacks: &mut i32, _owner: &mut KeyPage) {}
+}
+
+//damage equal to stacks at scene end, then a third of the stacks is lost
+pub struct Burn;
+
+impl StatusEffect for Burn {
+    fn name(&self) -> &str {
+        "Burn"
+    }
+
+    fn kind(&self) -> StatusKind {
+        StatusKind::Debuff
+    }
+
+    fn scene_end(&self, stacks: &mut i32, owner: &mut KeyPage) {
+        lose_health(owner, *stacks);
+        *stacks = *stacks * 2 / 3;
+    }
+}
+
+//each stack fixes one rolled die to its minimum face
+pub struct Paralysis;
+
+impl StatusEffect for Paralysis {
+    fn name(&self) -> &str {
+        "Paralysis"
+    }
+
+    fn kind(&self) -> StatusKind {
+        StatusKind::Debuff
+    }
+
+    fn dice_roll(&self, stacks: &mut i32, _owner: &mut KeyPage, dice: &mut Dice) {
+        dice.fix(dice.range().0);
+        *stacks -= 1;
+    }
+}
+
+//+stacks / -stacks power on attack dice, counters included
+pub struct Strength;
+
+impl StatusEffect for Strength {
+    fn name(&self) -> &str {
+        "Strength"
+    }
+
+    fn kind(&self) -> StatusKind {
+        StatusKind::Buff
+    }
+
+    fn power(&self, stacks: i32, dtype: &DiceType) -> i32 {
+        if DiceFilter::Offensive.matches(dtype) {
+            stacks
+        } else {
+            0
+        }
+    }
+}
+
+pub struct Feeble;
+
+impl StatusEffect for Feeble {
+    fn name(&self) -> &str {
+        "Feeble"
+    }
+
+    fn kind(&self) -> StatusKind {
+        StatusKind::Debuff
+    }
+
+    fn power(&self, stacks: i32, dtype: &DiceType) -> i32 {
+        if DiceFilter::Offensive.matches(dtype) {
+            -stacks
+        } else {
+            0
+        }
+    }
+}
+
+//+stacks / -stacks power on block and evade dice, counters included
+pub struct Endurance;
+
+impl StatusEffect for Endurance {
+    fn name(&self) -> &str {
+        "Endurance"
+    }
+
+    fn kind(&self) -> StatusKind {
+        StatusKind::Buff
+    }
+
+    fn power(&self, stacks: i32, dtype: &DiceType) -> i32 {
+        if DiceFilter::Defensive.matches(dtype) {
+            stacks
+        } else {
+            0
+        }
+    }
+}
+
+pub struct Disarm;
+
+impl StatusEffect for Disarm {
+    fn name(&self) -> &str {
+        "Disarm"
+    }
+
+    fn kind(&self) -> StatusKind {
+        StatusKind::Debuff
+    }
+
+    fn power(&self, stacks: i32, dtype: &DiceType) -> i32 {
+        if DiceFilter::Defensive.matches(dtype) {
+            -stacks
+        } else {
+            0
+        }
+    }
+}
+
+//+stacks / -stacks on every speed die rolled while active
+pub struct Haste;
+
+impl StatusEffect for Haste {
+    fn name(&self) -> &str {
+        "Haste"
+    }
+
+    fn kind(&self) -> StatusKind {
+        StatusKind::Buff
+    }
+
+    fn power(&self, stacks: i32, dtype: &DiceType) -> i32 {
+        if *dtype == DiceType::Speed {
+            stacks
+        } else {
+            0
+        }
+    }
+}
+
+pub struct Bind;
+
+impl StatusEffect for Bind {
+    fn name(&self) -> &str {
+        "Bind"
+    }
+
+    fn kind(&self) -> StatusKind {
+        StatusKind::Debuff
+    }
+
+    fn power(&self, stacks: i32, dtype: &DiceType) -> i32 {
+        if *dtype == DiceType::Speed {
+            -stacks
+        } else {
+            0
+        }
+    }
+}
+
+//each stack takes 1 off the health damage of every hit, after resistance
+pub struct Protection;
+
+impl StatusEffect for Protection {
+    fn name(&self) -> &str {
+        "Protection"
+    }
+
+    fn kind(&self) -> StatusKind {
+        StatusKind::Buff
+    }
+
+    fn incoming(&self, stacks: i32) -> DamageModifiers {
+        let mut modifiers = DamageModifiers::default();
+        modifiers.health.reduction = stacks;
+        modifiers
+    }
+}
+
+pub struct StaggerProtection;
+
+impl StatusEffect for StaggerProtection {
+    fn name(&self) -> &str {
+        "Stagger Protection"
+    }
+
+    fn kind(&self) -> StatusKind {
+        StatusKind::Buff
+    }
+
+    fn incoming(&self, stacks: i32) -> DamageModifiers {
+        let mut modifiers = DamageModifiers::default();
+        modifiers.stagger.reduction = stacks;
+        modifiers
+    }
+}
+
+//each stack adds 1 health damage to every hit, after resistance
+pub struct Fragile;
+
+impl StatusEffect for Fragile {
+    fn name(&self) -> &str {
+        "Fragile"
+    }
+
+    fn kind(&self) -> StatusKind {
+        StatusKind::Debuff
+    }
+
+    fn incoming(&self, stacks: i32) -> DamageModifiers {
+        let mut modifiers = DamageModifiers::default();
+        modifiers.health.reduction = -stacks;
+        modifiers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::damage::Stage;
+    use crate::fixtures::slash_page;
+    use crate::object::{
+        AttackDice, CombatDice, CombatPage, CombatPageBuilder, DefenseDice, DiceType,
+        KeyPageBuilder, NormalDice, Resistance,
+    };
+    use crate::power::PowerSource;
+
+    //+stacks power on every die, loses a stack per hit taken, halves at scene end
+    struct Focus;
+
+    impl StatusEffect for Focus {
+        fn name(&self) -> &str {
+            "Focus"
+        }
+
+        fn kind(&self) -> StatusKind {
+            StatusKind::Buff
+        }
+
+        fn dice_roll(&self, stacks: &mut i32, _owner: &mut KeyPage, dice: &mut Dice) {
+            dice.add_power(PowerSource::Status, *stacks);
+            dice.fix(dice.raw());
+        }
+
+        fn on_hit_taken(&self, stacks: &mut i32, _owner: &mut KeyPage, _attacker: &mut KeyPage) {
+            *stacks -= 1;
+        }
+
+        fn scene_end(&self, stacks: &mut i32, _owner: &mut KeyPage) {
+            *stacks /= 2;
+        }
+    }
+
+    //heals its owner at scene start and spreads to whoever it hits
+    struct Spread;
+
+    impl StatusEffect for Spread {
+        fn name(&self) -> &str {
+            "Spread"
+        }
+
+        fn kind(&self) -> StatusKind {
+            StatusKind::Debuff
+        }
+
+        fn scene_start(&self, stacks: &mut i32, owner: &mut KeyPage) {
+            owner.curhealth += *stacks;
+        }
+
+        fn on_hit(&self, stacks: &mut i32, _owner: &mut KeyPage, target: &mut KeyPage) {
+            target.statuses.add(Arc::new(Spread), *stacks);
+        }
+    }
+
+    #[test]
+    fn status_stacks() {
+        let mut container = StatusContainer::default();
+        container.add(Arc::new(Focus), 2);
+        container.add(Arc::new(Focus), 1);
+        container.add_next(Arc::new(Focus), 4);
+        assert_eq!(container.stacks("Focus"), 3);
+        assert_eq!(container.next_stacks("Focus"), 4);
+        assert_eq!(container.iter().count(), 1);
+        container.reduce("Focus", 5);
+        assert_eq!(container.stacks("Focus"), 0);
+        assert!(container.get("Focus").is_some());
+        assert!(container.remove("Focus").is_some());
+        assert!(container.is_empty());
+    }
+
+    #[test]
+    fn status_hooks() {
+        let mut own = KeyPageBuilder::new().health(50).stagger(50).build();
+        let mut other = KeyPageBuilder::new().health(50).stagger(50).build();
+        own.statuses.add(Arc::new(Focus), 4);
+        own.statuses.add_next(Arc::new(Spread), 3);
+        own.curhealth = 40;
+
+        own.start_scene();
+        assert_eq!(own.statuses.stacks("Spread"), 3);
+        assert_eq!(own.health(), 43);
+
+        //2 + 4 focus beats 5 and the hit spreads Spread
+        let report = slash_page(2).clash(
+            slash_page(5),
+            &mut own,
+            &mut other,
+            &mut crate::rng::seeded(0),
+        );
+        assert_eq!(report.records[0].own_value(), Some(6));
+        assert_eq!(other.health(), 44);
+        assert_eq!(other.statuses.stacks("Spread"), 3);
+
+        //other hits back, own loses a focus stack
+        let _ = slash_page(9).clash(
+            slash_page(1),
+            &mut other,
+            &mut own,
+            &mut crate::rng::seeded(0),
+        );
+        assert_eq!(own.statuses.stacks("Focus"), 3);
+
+        own.end_scene();
+        assert_eq!(own.statuses.stacks("Focus"), 1);
+        assert_eq!(own.statuses.stacks("Spread"), 0);
+        assert!(own.statuses.get("Spread").is_none());
+    }
+
+    #[test]
+    fn bleed_burn_paralysis() {
+        let mut own = KeyPageBuilder::new()
+            .health(50)
+            .stagger(50)
+            .hslash_resistance(Resistance::Fatal)
+            .build();
+        let mut other = KeyPageBuilder::new().health(50).stagger(50).build();
+        own.statuses.add(Arc::new(Bleed), 6);
+        own.statuses.add(Arc::new(Paralysis), 1);
+        own.statuses.add_next(Arc::new(Burn), 3);
+
+        //block die: no bleed, paralysis pins the 2-9 roll to 2
+        let block = CombatPageBuilder::new()
+            .dice(Dice::new(
+                DiceType::Combat(CombatDice::Normal(NormalDice::Defense(DefenseDice::Block))),
+                2,
+                9,
+            ))
+            .build();
+        let report = block.clash(
+            slash_page(1),
+            &mut own,
+            &mut other,
+            &mut crate::rng::seeded(0),
+        );
+        assert_eq!(report.records[0].own_value(), Some(2));
+        assert_eq!(own.statuses.stacks("Paralysis"), 0);
+        assert_eq!(own.health(), 50);
+
+        //slash die: 6 bleed, untyped so fatal slash does not double it
+        let _ = slash_page(3).clash(
+            slash_page(1),
+            &mut own,
+            &mut other,
+            &mut crate::rng::seeded(0),
+        );
+        assert_eq!(own.health(), 44);
+        assert_eq!(own.stagger(), 50);
+        assert_eq!(own.statuses.stacks("Bleed"), 4);
+
+        own.start_scene();
+        own.end_scene();
+        assert_eq!(own.health(), 41);
+        assert_eq!(own.statuses.stacks("Burn"), 2);
+        assert_eq!(own.statuses.stacks("Bleed"), 4);
+    }
+
+    #[test]
+    fn power_statuses() {
+        let counter =
+            DiceType::Combat(CombatDice::Counter(NormalDice::Defense(DefenseDice::Evade)));
+        let attack = DiceType::Combat(CombatDice::Normal(NormalDice::Attack(AttackDice::Blunt)));
+        let mut own = KeyPageBuilder::new().health(50).stagger(50).build();
+        let mut other = KeyPageBuilder::new().health(50).stagger(50).build();
+        own.statuses.add(Arc::new(Strength), 3);
+        own.statuses.add(Arc::new(Feeble), 1);
+        own.statuses.add(Arc::new(Endurance), 2);
+        other.statuses.add(Arc::new(Disarm), 2);
+        assert_eq!(own.statuses.power(&attack), 2);
+        assert_eq!(own.statuses.power(&counter), 2);
+        assert_eq!(other.statuses.power(&counter), -2);
+        assert_eq!(other.statuses.power(&attack), 0);
+
+        //4 + 2 beats 5 only thanks to strength
+        let report = slash_page(4).clash(
+            slash_page(5),
+            &mut own,
+            &mut other,
+            &mut crate::rng::seeded(0),
+        );
+        assert_eq!(report.records[0].winner, crate::object::Winner::Own);
+        assert_eq!(report.records[0].own_value(), Some(6));
+        assert_eq!(other.health(), 44);
+
+        own.end_scene();
+        assert_eq!(own.statuses.power(&attack), 0);
+
+        //a reserved die keeps its power once, however often it is rolled
+        let block = Dice::new(
+            DiceType::Combat(CombatDice::Normal(NormalDice::Defense(DefenseDice::Block))),
+            3,
+            3,
+        );
+        let mut rng = crate::rng::seeded(0);
+        own.statuses.add(Arc::new(Endurance), 2);
+        let page = CombatPageBuilder::new().dice(block).build();
+        let _ = page.clash(CombatPage::default(), &mut own, &mut other, &mut rng);
+        assert_eq!(own.reserved[0].cur(), 5);
+        let report = slash_page(1).one_sided(&mut other, &mut own, &mut rng);
+        assert_eq!(report.records[0].other_value(), Some(5));
+
+        //status power the die already had stays on top
+        let mut page = slash_page(3);
+        page.dices[0].add_power(PowerSource::Status, 5);
+        let mut fresh = KeyPageBuilder::new().health(50).stagger(50).build();
+        fresh.statuses.add(Arc::new(Strength), 1);
+        let report = page.clash(slash_page(1), &mut fresh, &mut other, &mut rng);
+        assert_eq!(report.records[0].own_value(), Some(9));
+    }
+
+    #[test]
+    fn haste_bind() {
+        let speed = |x| Dice::new(DiceType::Speed, x, x);
+        let mut own = KeyPageBuilder::new()
+            .speed(speed(3))
+            .speed(speed(5))
+            .build();
+        let mut other = KeyPageBuilder::new().speed(speed(4)).build();
+        own.statuses.add_next(Arc::new(Haste), 2);
+        other.statuses.add_next(Arc::new(Bind), 6);
+        own.start_scene_with(&mut crate::rng::seeded(0));
+        other.start_scene_with(&mut crate::rng::seeded(0));
+
+        assert_eq!(own.get(0).unwrap().cur(), 5);
+        assert_eq!(own.get(1).unwrap().cur(), 7);
+        //bind never takes a die under 1
+        assert_eq!(other.get(0).unwrap().cur(), 1);
+        assert_eq!(own.speed_order(), vec![1, 0]);
+        assert!(own.outspeeds(0, &other, 0));
+        assert!(!other.outspeeds(0, &own, 1));
+
+        //haste lasts one scene
+        own.end_scene();
+        own.start_scene_with(&mut crate::rng::seeded(0));
+        assert_eq!(own.get(1).unwrap().cur(), 5);
+    }
+
+    #[test]
+    fn protection_fragile() {
+        let mut own = KeyPageBuilder::new().health(50).stagger(50).build();
+        let mut other = KeyPageBuilder::new()
+            .health(50)
+            .stagger(50)
+            .hslash_resistance(Resistance::Weak)
+            .build();
+        other.statuses.add(Arc::new(Protection), 4);
+        other.statuses.add(Arc::new(Fragile), 1);
+        other.statuses.add(Arc::new(StaggerProtection), 2);
+        assert_eq!(other.statuses.incoming().health.reduction, 3);
+
+        //8 slash x1.5 weak = 12, then -4 protection +1 fragile
+        let report = slash_page(8).clash(
+            slash_page(1),
+            &mut own,
+            &mut other,
+            &mut crate::rng::seeded(0),
+        );
+        let breakdown = &report.records[0].other_breakdown;
+        assert_eq!(breakdown.health.get(Stage::Resistance), Some(12.0));
+        assert_eq!(breakdown.health.get(Stage::Reduction), Some(9.0));
+        assert_eq!(breakdown.stagger.value, 6);
+        assert_eq!(other.health(), 41);
+
+        //bleed is not a hit, protection does not reduce it
+        other.statuses.add(Arc::new(Bleed), 3);
+        let _ = slash_page(1).clash(
+            slash_page(1),
+            &mut other,
+            &mut own,
+            &mut crate::rng::seeded(0),
+        );
+        assert_eq!(other.health(), 38);
+
+        other.end_scene();
+        assert_eq!(other.statuses.incoming(), DamageModifiers::default());
+    }
+}